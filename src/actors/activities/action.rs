@@ -1,12 +1,28 @@
+use serde::Deserialize;
+
 use crate::core::types::Direction;
 
-#[derive(Debug, Copy, Clone, PartialEq)]
+#[derive(Debug, Copy, Clone, PartialEq, Deserialize)]
 pub enum Action {
     Wait,
     Move(Direction),
     Turn(Direction),
     InitiateAttack,
     Attack,
+    /// Winds up a sweep across a wide arc in front of the actor.
+    InitiateSweep,
+    Sweep,
+    UseStairs,
+    /// Opens the door straight ahead.
+    Open,
+    /// Closes the door straight ahead, unless something lies or stands in it.
+    Close,
+    /// Pushes the actor straight ahead one tile further along the shover's facing.
+    Shove,
+    /// Lights or puts out the light the actor carries.
+    ToggleLight,
+    /// Launches a projectile straight ahead.
+    Fire,
 }
 
 impl Default for Action {
@@ -2,14 +2,26 @@ use bevy::prelude::*;
 use std::convert::*;
 
 use crate::{
-    actors::{effects::Effect, Action, Activity, Actor, Weapon},
+    actors::{
+        constants::{
+            ATTACK_LOUDNESS, FIRE_LOUDNESS, MOVE_LOUDNESS, MOVE_TIME, PROJECTILE_RANGE,
+            PROJECTILE_VELOCITY, SHOVE_LOUDNESS, SHOVE_RECOVERY_TIME, STAGGER_TIME,
+            WALL_HIT_LOUDNESS,
+        },
+        effects::{Effect, Hazard, Telegraph},
+        projectile_glyph, Action, Activity, Actor, Projectile, PushEvent, Stat, StrikeEvent,
+        Weapon,
+    },
     core::types::{Cardinal, Direction, Facing, Int, Predicate},
     core::{
         types::{GridPos, Increment},
         TimeIncrementEvent,
     },
     util::{algorithms::geometry::chessboard_rotate_and_place, helpers::GridRotator},
-    world::{Renderable, Viewshed, WorldMap},
+    world::{
+        close_door, open_door, Item, Keyring, LevelChangeEvent, LightSource, NoiseEvent,
+        OpensDoors, Renderable, TerrainChangedEvent, TileType, Viewshed, WorldMap, BASE_MOVE_COST,
+    },
 };
 
 pub fn progress_activities(
@@ -27,9 +39,10 @@ pub fn progress_activities(
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 pub fn do_activities(
     mut commands: Commands,
-    world_map: Res<WorldMap>,
+    mut world_map: ResMut<WorldMap>,
     mut actors: Query<
         (
             Entity,
@@ -38,44 +51,203 @@ pub fn do_activities(
             &mut GridPos,
             &mut Viewshed,
             &Activity,
+            Option<&OpensDoors>,
+            Option<&mut Keyring>,
+            Option<&mut LightSource>,
         ),
         Without<Weapon>,
     >,
-    mut weapons: Query<&mut GridPos, With<Weapon>>,
+    mut weapons: Query<(&mut GridPos, &Weapon)>,
+    obstacles: Query<(), Or<(With<Item>, With<Hazard>)>>,
+    mut noise_writer: EventWriter<NoiseEvent>,
+    mut strike_writer: EventWriter<StrikeEvent>,
+    mut push_writer: EventWriter<PushEvent>,
+    mut level_writer: EventWriter<LevelChangeEvent>,
+    mut terrain_writer: EventWriter<TerrainChangedEvent>,
 ) {
-    for (entity, children, mut actor, mut pos, mut viewshed, activity) in actors.iter_mut() {
+    for (
+        entity,
+        children,
+        mut actor,
+        mut pos,
+        mut viewshed,
+        activity,
+        opens_doors,
+        mut keyring,
+        mut light,
+    ) in actors.iter_mut()
+    {
         if activity.time_to_complete == 0 {
             let mut new_activity: Option<Activity> = None;
+            let weapon = children
+                .iter()
+                .copied()
+                .find(|child| weapons.get(*child).is_ok());
 
             match activity.action {
                 Action::Move(direction) => {
-                    let area = &world_map.get_area_from_pos(&pos.0).unwrap().1;
-                    pos.0 = do_move(&pos.0, direction, actor.facing, &|pos| {
-                        area.is_blocking(pos)
-                    });
-                    let mut weapon_pos = weapons.get_mut(*children.get(0).unwrap()).unwrap();
-                    weapon_pos.0 = compute_next_position(Direction::Forward, actor.facing, &pos.0);
-
-                    // TODO: replace with event writer
-                    viewshed.dirty = true;
+                    let ahead = compute_next_position(direction, actor.facing, &pos.0);
+                    if opens_doors.is_some() && world_map.is_closed_door(&ahead) {
+                        // Walking into a door spends the step on opening it.
+                        if open_door(&mut world_map, &ahead, keyring.as_deref_mut()) {
+                            terrain_writer.send(TerrainChangedEvent { position: ahead });
+                        }
+                    } else {
+                        let next = do_move(&pos.0, direction, actor.facing, &|pos| {
+                            world_map.is_blocking(pos)
+                                || world_map.blocking_entity_at(pos).is_some()
+                        });
+                        // A blocked move makes no sound and changes nothing in view.
+                        if next != pos.0 {
+                            pos.0 = next;
+                            noise_writer.send(NoiseEvent {
+                                origin: pos.0,
+                                loudness: MOVE_LOUDNESS,
+                            });
+                            if let Some(weapon) = weapon {
+                                let (mut weapon_pos, _) = weapons.get_mut(weapon).unwrap();
+                                weapon_pos.0 =
+                                    compute_next_position(Direction::Forward, actor.facing, &pos.0);
+                            }
+
+                            // TODO: replace with event writer
+                            viewshed.dirty = true;
+
+                            // Slow tiles keep the actor busy for the rest of the step.
+                            let cost = world_map
+                                .tile_at(&pos.0)
+                                .map_or(BASE_MOVE_COST, |tile| tile.which().cost());
+                            if cost > BASE_MOVE_COST {
+                                new_activity = Some(Activity {
+                                    action: Action::Wait,
+                                    time_to_complete: MOVE_TIME
+                                        * (cost - BASE_MOVE_COST) as Increment
+                                        / BASE_MOVE_COST as Increment,
+                                });
+                            }
+                        }
+                    }
                 }
                 Action::Turn(direction) => {
                     actor.facing = rotate_facing(actor.facing, direction.into());
 
-                    let mut weapon_pos = weapons.get_mut(*children.get(0).unwrap()).unwrap();
-                    weapon_pos.0 = compute_next_position(Direction::Forward, actor.facing, &pos.0);
+                    if let Some(weapon) = weapon {
+                        let (mut weapon_pos, _) = weapons.get_mut(weapon).unwrap();
+                        weapon_pos.0 =
+                            compute_next_position(Direction::Forward, actor.facing, &pos.0);
+                    }
 
                     viewshed.dirty = true;
                 }
-                Action::InitiateAttack => {
+                Action::InitiateAttack | Action::InitiateSweep => {
+                    let strike = match activity.action {
+                        Action::InitiateSweep => Action::Sweep,
+                        _ => Action::Attack,
+                    };
+                    let windup = actor.sheet.stat(Stat::Windup) as Increment;
                     new_activity = Some(Activity {
-                        action: Action::Attack,
-                        time_to_complete: 60,
+                        action: strike,
+                        time_to_complete: windup,
+                    });
+                    telegraph_attack(entity, &pos.0, actor.facing, strike, windup, &mut commands);
+                }
+                Action::Attack | Action::Sweep => {
+                    let struck = do_attack(&pos.0, actor.facing, activity.action, &mut commands);
+                    noise_writer.send(NoiseEvent {
+                        origin: pos.0,
+                        loudness: ATTACK_LOUDNESS,
+                    });
+                    if let Some(wall) = struck.iter().find(|pos| world_map.is_blocking(pos)) {
+                        noise_writer.send(NoiseEvent {
+                            origin: *wall,
+                            loudness: WALL_HIT_LOUDNESS,
+                        });
+                    }
+                    let heavy = weapon
+                        .and_then(|weapon| weapons.get(weapon).ok())
+                        .map_or(false, |(_, weapon)| weapon.knockback);
+                    strike_writer.send(StrikeEvent {
+                        attacker: entity,
+                        positions: struck,
+                        damage: actor.sheet.stat(Stat::MeleeDamage),
+                        knockback: heavy.then(|| actor.facing),
                     });
-                    telegraph_attack(&pos.0, actor.facing, &mut commands);
                 }
-                Action::Attack => {
-                    do_attack(&pos.0, actor.facing, &mut commands);
+                Action::UseStairs => {
+                    let delta = match world_map.tile_at(&pos.0).map(|tile| tile.which()) {
+                        Some(TileType::DOWN_STAIRS) => Some(1),
+                        Some(TileType::UP_STAIRS) => Some(-1),
+                        _ => None,
+                    };
+                    if let Some(delta) = delta {
+                        level_writer.send(LevelChangeEvent { entity, delta });
+                    }
+                }
+                Action::Open => {
+                    let door = compute_next_position(Direction::Forward, actor.facing, &pos.0);
+                    if opens_doors.is_some()
+                        && open_door(&mut world_map, &door, keyring.as_deref_mut())
+                    {
+                        terrain_writer.send(TerrainChangedEvent { position: door });
+                    }
+                }
+                Action::Close => {
+                    let door = compute_next_position(Direction::Forward, actor.facing, &pos.0);
+                    // The closer's own weapon always stands in the doorway and doesn't count.
+                    let is_clear = world_map.blocking_entity_at(&door).is_none()
+                        && !world_map.occupants_at(&door).iter().any(|occupant| {
+                            !children.contains(&occupant.entity)
+                                && obstacles.get(occupant.entity).is_ok()
+                        });
+                    if is_clear && close_door(&mut world_map, &door) {
+                        terrain_writer.send(TerrainChangedEvent { position: door });
+                    }
+                }
+                Action::Shove => {
+                    let ahead = compute_next_position(Direction::Forward, actor.facing, &pos.0);
+                    if let Some(target) = world_map.blocking_entity_at(&ahead) {
+                        push_writer.send(PushEvent {
+                            target,
+                            direction: actor.facing,
+                            stagger: STAGGER_TIME,
+                        });
+                        noise_writer.send(NoiseEvent {
+                            origin: ahead,
+                            loudness: SHOVE_LOUDNESS,
+                        });
+                        new_activity = Some(Activity {
+                            action: Action::Wait,
+                            time_to_complete: SHOVE_RECOVERY_TIME,
+                        });
+                    }
+                }
+                Action::ToggleLight => {
+                    if let Some(light) = light.as_mut() {
+                        light.lit = !light.lit;
+                    }
+                }
+                Action::Fire => {
+                    let ahead = compute_next_position(Direction::Forward, actor.facing, &pos.0);
+                    commands
+                        .spawn()
+                        .insert(GridPos(pos.0))
+                        .insert(Renderable {
+                            glyph: projectile_glyph(actor.facing),
+                            fg: Color::WHITE,
+                            bg: Color::BLACK,
+                        })
+                        .insert(Projectile::aimed(
+                            entity,
+                            pos.0,
+                            ahead,
+                            PROJECTILE_RANGE,
+                            PROJECTILE_VELOCITY,
+                            actor.sheet.stat(Stat::RangedDamage),
+                        ));
+                    noise_writer.send(NoiseEvent {
+                        origin: pos.0,
+                        loudness: FIRE_LOUDNESS,
+                    });
                 }
                 _ => (),
             }
@@ -93,7 +265,7 @@ fn compute_facing(direction: Direction, cardinal: Cardinal) -> Facing {
     direction * cardinal
 }
 
-fn rotate_facing(cardinal: Cardinal, offset: Int) -> Cardinal {
+pub fn rotate_facing(cardinal: Cardinal, offset: Int) -> Cardinal {
     let cardinal_index: Int = cardinal.into();
     ((cardinal_index + offset) % 8).into()
 }
@@ -122,7 +294,7 @@ fn slide(
     result_position
 }
 
-fn do_move(
+pub fn do_move(
     pos: &IVec2,
     direction: Direction,
     cardinal: Cardinal,
@@ -133,20 +305,19 @@ fn do_move(
     if is_blocking(&next_position) {
         next_position = slide(*pos, direction, cardinal, is_blocking);
     }
-    next_position.x = next_position.x.clamp(0, 79);
-    next_position.y = next_position.y.clamp(0, 49);
 
     next_position
 }
 
-fn compute_next_position(direction: Direction, cardinal: Cardinal, pos: &IVec2) -> IVec2 {
+pub fn compute_next_position(direction: Direction, cardinal: Cardinal, pos: &IVec2) -> IVec2 {
     let delta = IVec2::new(0, -1);
     let facing = compute_facing(direction, cardinal);
     facing.inverse().rot_i(&delta) + *pos
 }
 
-fn telegraph_attack(origin: &IVec2, facing: Cardinal, commands: &mut Commands) {
-    let pattern: Vec<IVec2> = vec![
+/// Tiles struck by an attack, relative to an attacker facing north.
+pub fn attack_pattern() -> Vec<IVec2> {
+    vec![
         IVec2::new(0, -1),
         IVec2::new(0, -2),
         IVec2::new(0, -3),
@@ -155,33 +326,64 @@ fn telegraph_attack(origin: &IVec2, facing: Cardinal, commands: &mut Commands) {
         IVec2::new(1, -3),
         IVec2::new(2, -3),
         IVec2::new(-1, -4),
-    ];
+    ]
+}
+
+/// Tiles struck by a sweep, relative to an attacker facing north. Wider but shorter
+/// than an ordinary attack.
+pub fn sweep_pattern() -> Vec<IVec2> {
+    vec![
+        IVec2::new(-1, 0),
+        IVec2::new(1, 0),
+        IVec2::new(-1, -1),
+        IVec2::new(0, -1),
+        IVec2::new(1, -1),
+        IVec2::new(-2, -2),
+        IVec2::new(-1, -2),
+        IVec2::new(0, -2),
+        IVec2::new(1, -2),
+        IVec2::new(2, -2),
+    ]
+}
 
-    let positions: Vec<IVec2> = chessboard_rotate_and_place(origin, &pattern, facing.into());
+fn strike_pattern(strike: Action) -> Vec<IVec2> {
+    match strike {
+        Action::Sweep => sweep_pattern(),
+        _ => attack_pattern(),
+    }
+}
+
+fn telegraph_attack(
+    attacker: Entity,
+    origin: &IVec2,
+    facing: Cardinal,
+    strike: Action,
+    windup: Increment,
+    commands: &mut Commands,
+) {
+    let positions: Vec<IVec2> =
+        chessboard_rotate_and_place(origin, &strike_pattern(strike), facing.into());
     let marker = Marker {
-        time_left: 60,
+        time_left: windup,
         renderable: Renderable {
             glyph: '!',
             fg: Color::RED,
             bg: Color::ANTIQUE_WHITE,
         },
     };
-    place_markers(&positions, marker, commands);
+    for telegraph in place_markers(&positions, marker, commands) {
+        commands.entity(telegraph).insert(Telegraph { attacker });
+    }
 }
 
-fn do_attack(origin: &IVec2, cardinal: Cardinal, commands: &mut Commands) {
-    let pattern: Vec<IVec2> = vec![
-        IVec2::new(0, -1),
-        IVec2::new(0, -2),
-        IVec2::new(0, -3),
-        IVec2::new(-1, -3),
-        IVec2::new(-2, -3),
-        IVec2::new(1, -3),
-        IVec2::new(2, -3),
-        IVec2::new(-1, -4),
-    ];
-
-    let positions: Vec<IVec2> = chessboard_rotate_and_place(origin, &pattern, cardinal.into());
+fn do_attack(
+    origin: &IVec2,
+    cardinal: Cardinal,
+    strike: Action,
+    commands: &mut Commands,
+) -> Vec<IVec2> {
+    let positions: Vec<IVec2> =
+        chessboard_rotate_and_place(origin, &strike_pattern(strike), cardinal.into());
     let marker = Marker {
         time_left: 30,
         renderable: Renderable {
@@ -191,6 +393,7 @@ fn do_attack(origin: &IVec2, cardinal: Cardinal, commands: &mut Commands) {
         },
     };
     place_markers(&positions, marker, commands);
+    positions
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -199,16 +402,20 @@ struct Marker {
     renderable: Renderable,
 }
 
-fn place_markers(positions: &[IVec2], marker: Marker, commands: &mut Commands) {
-    positions.iter().for_each(|pos| {
-        commands
-            .spawn()
-            .insert(GridPos(*pos))
-            .insert(marker.renderable)
-            .insert(Effect {
-                time_left: marker.time_left,
-            });
-    });
+fn place_markers(positions: &[IVec2], marker: Marker, commands: &mut Commands) -> Vec<Entity> {
+    positions
+        .iter()
+        .map(|pos| {
+            commands
+                .spawn()
+                .insert(GridPos(*pos))
+                .insert(marker.renderable)
+                .insert(Effect {
+                    time_left: marker.time_left,
+                })
+                .id()
+        })
+        .collect()
 }
 
 #[cfg(test)]
@@ -216,17 +423,20 @@ mod tests {
     use bevy::prelude::*;
 
     use crate::{
-        actors::{Action, Activity, ActorBundle},
+        actors::{Action, Activity, ActorBundle, PushEvent, StrikeEvent, WeaponBundle},
         core::types::{Direction, GridPos},
-        test,
-        world::{AreaGrid, TileType},
+        test::{self, helpers::world::sent_events},
+        world::{
+            systems::update_spatial_index, AreaGrid, Item, LevelChangeEvent, NoiseEvent,
+            OpensDoors, TerrainChangedEvent, TileType, Viewshed, WorldMap,
+        },
     };
 
     use super::{do_activities, slide};
 
     fn test_map() -> AreaGrid {
         AreaGrid {
-            tiles: vec![TileType::Floor; 80 * 50],
+            tiles: vec![TileType::FLOOR; 80 * 50],
             width: 80,
             height: 50,
             revealed: vec![false; 80 * 50],
@@ -235,15 +445,40 @@ mod tests {
         }
     }
 
-    #[test]
-    fn consume_activity_upon_completion() {
+    fn test_world() -> World {
         let mut world = World::new();
-        world.spawn().insert(test_map());
-        let entity = world
+        let mut world_map = WorldMap::default();
+        world_map.insert_offset(&IVec2::ZERO, test_map());
+        world.insert_resource(world_map);
+        world.insert_resource(Events::<NoiseEvent>::default());
+        world.insert_resource(Events::<StrikeEvent>::default());
+        world.insert_resource(Events::<PushEvent>::default());
+        world.insert_resource(Events::<LevelChangeEvent>::default());
+        world.insert_resource(Events::<TerrainChangedEvent>::default());
+        world
+    }
+
+    fn spawn_actor(world: &mut World, position: IVec2, activity: Activity) -> Entity {
+        world
             .spawn()
-            .insert_bundle(ActorBundle::default())
-            .insert(Activity::default())
-            .id();
+            .insert_bundle(ActorBundle {
+                position: GridPos(position),
+                ..Default::default()
+            })
+            .insert(activity)
+            .with_children(|actor| {
+                actor.spawn().insert_bundle(WeaponBundle {
+                    position: GridPos(position + IVec2::new(0, -1)),
+                    ..Default::default()
+                });
+            })
+            .id()
+    }
+
+    #[test]
+    fn consume_activity_upon_completion() {
+        let mut world = test_world();
+        let entity = spawn_actor(&mut world, IVec2::ZERO, Activity::default());
 
         let mut stage = SystemStage::single(do_activities.system());
         stage.run(&mut world);
@@ -253,16 +488,15 @@ mod tests {
 
     #[test]
     fn move_action() {
-        let mut world = World::new();
-        world.spawn().insert(test_map());
-        let entity = world
-            .spawn()
-            .insert_bundle(ActorBundle::default())
-            .insert(Activity {
+        let mut world = test_world();
+        let entity = spawn_actor(
+            &mut world,
+            IVec2::ZERO,
+            Activity {
                 action: Action::Move(Direction::Back),
                 ..Default::default()
-            })
-            .id();
+            },
+        );
 
         let mut stage = SystemStage::single(do_activities.system());
         stage.run(&mut world);
@@ -273,6 +507,271 @@ mod tests {
         assert_eq!(IVec2::new(0, 1), position.0);
     }
 
+    #[test]
+    fn cannot_leave_the_world() {
+        let mut world = test_world();
+        let entity = spawn_actor(
+            &mut world,
+            IVec2::ZERO,
+            Activity {
+                action: Action::Move(Direction::Forward),
+                ..Default::default()
+            },
+        );
+        world.get_mut::<Viewshed>(entity).unwrap().dirty = false;
+
+        let mut stage = SystemStage::single(do_activities.system());
+        stage.run(&mut world);
+
+        let position = world.get::<GridPos>(entity).unwrap();
+        assert_eq!(IVec2::ZERO, position.0);
+        assert!(sent_events::<NoiseEvent>(&world).is_empty());
+        assert!(!world.get::<Viewshed>(entity).unwrap().dirty);
+    }
+
+    #[test]
+    fn walks_into_neighbouring_area() {
+        let mut world = test_world();
+        let mut east = test_map();
+        east.set_tile(&IVec2::new(0, 10), TileType::FLOOR);
+        world
+            .get_resource_mut::<WorldMap>()
+            .unwrap()
+            .insert_offset(&IVec2::new(80, 0), east);
+
+        let entity = spawn_actor(
+            &mut world,
+            IVec2::new(79, 10),
+            Activity {
+                action: Action::Move(Direction::Right),
+                ..Default::default()
+            },
+        );
+
+        let mut stage = SystemStage::single(do_activities.system());
+        stage.run(&mut world);
+
+        let position = world.get::<GridPos>(entity).unwrap();
+        assert_eq!(IVec2::new(80, 10), position.0);
+    }
+
+    #[test]
+    fn stairs_send_level_change() {
+        let mut world = test_world();
+        world
+            .get_resource_mut::<WorldMap>()
+            .unwrap()
+            .tile_at_mut(&IVec2::new(5, 5))
+            .unwrap()
+            .set_type(TileType::DOWN_STAIRS);
+        let entity = spawn_actor(
+            &mut world,
+            IVec2::new(5, 5),
+            Activity {
+                action: Action::UseStairs,
+                ..Default::default()
+            },
+        );
+
+        let mut stage = SystemStage::single(do_activities.system());
+        stage.run(&mut world);
+
+        let events = world.get_resource::<Events<LevelChangeEvent>>().unwrap();
+        let mut reader = events.get_reader();
+        let sent: Vec<&LevelChangeEvent> = reader.iter(events).collect();
+        assert_eq!(1, sent.len());
+        assert_eq!(entity, sent[0].entity);
+        assert_eq!(1, sent[0].delta);
+    }
+
+    #[test]
+    fn deep_water_slows_movement() {
+        let mut world = test_world();
+        let water = TileType::named("deep water").unwrap();
+        world
+            .get_resource_mut::<WorldMap>()
+            .unwrap()
+            .tile_at_mut(&IVec2::new(5, 6))
+            .unwrap()
+            .set_type(water);
+        let entity = spawn_actor(
+            &mut world,
+            IVec2::new(5, 5),
+            Activity {
+                action: Action::Move(Direction::Back),
+                ..Default::default()
+            },
+        );
+
+        let mut stage = SystemStage::single(do_activities.system());
+        stage.run(&mut world);
+
+        let activity = world.get::<Activity>(entity).unwrap();
+        assert_eq!(Action::Wait, activity.action);
+        assert!(activity.time_to_complete > 0);
+    }
+
+    fn door_ahead(world: &mut World) -> IVec2 {
+        let door = IVec2::new(5, 6);
+        world
+            .get_resource_mut::<WorldMap>()
+            .unwrap()
+            .tile_at_mut(&door)
+            .unwrap()
+            .set_type(TileType::CLOSED_DOOR);
+        door
+    }
+
+    #[test]
+    fn walking_into_a_door_opens_it() {
+        let mut world = test_world();
+        let door = door_ahead(&mut world);
+        let entity = spawn_actor(
+            &mut world,
+            IVec2::new(5, 5),
+            Activity {
+                action: Action::Move(Direction::Back),
+                ..Default::default()
+            },
+        );
+        world.entity_mut(entity).insert(OpensDoors);
+
+        let mut stage = SystemStage::single(do_activities.system());
+        stage.run(&mut world);
+
+        assert_eq!(IVec2::new(5, 5), world.get::<GridPos>(entity).unwrap().0);
+        let world_map = world.get_resource::<WorldMap>().unwrap();
+        assert_eq!(
+            TileType::OPEN_DOOR,
+            world_map.tile_at(&door).unwrap().which()
+        );
+        let events = world.get_resource::<Events<TerrainChangedEvent>>().unwrap();
+        assert_eq!(1, events.get_reader().iter(events).count());
+    }
+
+    #[test]
+    fn doors_stop_those_who_cannot_open_them() {
+        let mut world = test_world();
+        let door = door_ahead(&mut world);
+        let entity = spawn_actor(
+            &mut world,
+            IVec2::new(5, 5),
+            Activity {
+                action: Action::Move(Direction::Back),
+                ..Default::default()
+            },
+        );
+
+        let mut stage = SystemStage::single(do_activities.system());
+        stage.run(&mut world);
+
+        assert_ne!(door, world.get::<GridPos>(entity).unwrap().0);
+        let world_map = world.get_resource::<WorldMap>().unwrap();
+        assert_eq!(
+            TileType::CLOSED_DOOR,
+            world_map.tile_at(&door).unwrap().which()
+        );
+    }
+
+    fn close_door_ahead(world: &mut World, blocked: bool) -> TileType {
+        let door = IVec2::new(5, 4);
+        world
+            .get_resource_mut::<WorldMap>()
+            .unwrap()
+            .tile_at_mut(&door)
+            .unwrap()
+            .set_type(TileType::OPEN_DOOR);
+        spawn_actor(
+            world,
+            IVec2::new(5, 5),
+            Activity {
+                action: Action::Close,
+                ..Default::default()
+            },
+        );
+        if blocked {
+            world
+                .spawn()
+                .insert(Item {
+                    kind: "key".to_string(),
+                })
+                .insert(GridPos(door));
+        }
+
+        SystemStage::single(update_spatial_index.system()).run(world);
+        SystemStage::single(do_activities.system()).run(world);
+        let world_map = world.get_resource::<WorldMap>().unwrap();
+        world_map.tile_at(&door).unwrap().which()
+    }
+
+    #[test]
+    fn closing_a_door_ignores_the_own_weapon() {
+        let mut world = test_world();
+        assert_eq!(TileType::CLOSED_DOOR, close_door_ahead(&mut world, false));
+        assert_eq!(1, sent_events::<TerrainChangedEvent>(&world).len());
+    }
+
+    #[test]
+    fn items_keep_a_door_open() {
+        let mut world = test_world();
+        assert_eq!(TileType::OPEN_DOOR, close_door_ahead(&mut world, true));
+    }
+
+    #[test]
+    fn actors_block_each_other() {
+        let mut world = test_world();
+        let entity = spawn_actor(
+            &mut world,
+            IVec2::new(5, 5),
+            Activity {
+                action: Action::Move(Direction::Back),
+                ..Default::default()
+            },
+        );
+        // Walls either side of the blocked tile, so there is no sliding past.
+        spawn_actor(&mut world, IVec2::new(5, 6), Activity::default());
+        let mut world_map = world.get_resource_mut::<WorldMap>().unwrap();
+        world_map
+            .tile_at_mut(&IVec2::new(4, 6))
+            .unwrap()
+            .set_type(TileType::WALL);
+        world_map
+            .tile_at_mut(&IVec2::new(6, 6))
+            .unwrap()
+            .set_type(TileType::WALL);
+
+        SystemStage::single(update_spatial_index.system()).run(&mut world);
+        let mut stage = SystemStage::single(do_activities.system());
+        stage.run(&mut world);
+
+        assert_eq!(IVec2::new(5, 5), world.get::<GridPos>(entity).unwrap().0);
+    }
+
+    #[test]
+    fn shove_pushes_the_actor_ahead() {
+        let mut world = test_world();
+        let entity = spawn_actor(
+            &mut world,
+            IVec2::new(5, 5),
+            Activity {
+                action: Action::Shove,
+                ..Default::default()
+            },
+        );
+        let target = spawn_actor(&mut world, IVec2::new(5, 4), Activity::default());
+
+        SystemStage::single(update_spatial_index.system()).run(&mut world);
+        let mut stage = SystemStage::single(do_activities.system());
+        stage.run(&mut world);
+
+        let events = world.get_resource::<Events<PushEvent>>().unwrap();
+        let sent: Vec<&PushEvent> = events.get_reader().iter(events).collect();
+        assert_eq!(1, sent.len());
+        assert_eq!(target, sent[0].target);
+        let activity = world.get::<Activity>(entity).unwrap();
+        assert_eq!(Action::Wait, activity.action);
+    }
+
     #[test]
     fn slide_test() {
         let from = IVec2::ZERO;
@@ -4,36 +4,115 @@ use bevy::{
 };
 
 use crate::{
-    core::types::{Cardinal, GridPos, Int, Percentage},
+    actors::{
+        Adjustment, Attribute, Attributes, Modifier, ModifierSource, Stat, StatBreakdown, Statuses,
+    },
+    core::types::{Cardinal, GridPos, Int, Percentage, Real},
     world::{Renderable, Viewshed},
 };
 
-const BASE_HEALTH: Int = 100;
-const BASE_SPEED: Int = 100;
-
-#[derive(Debug, Component)]
+#[derive(Debug, Default, Clone, Component)]
 pub struct CharacterSheet {
-    pub health: Int, // maybe derive health from attributes
-    pub speed: Int,
-    // attributes...
+    pub attributes: Attributes,
+    modifiers: Vec<Modifier>,
 }
 
-impl Default for CharacterSheet {
-    fn default() -> Self {
-        Self {
-            health: BASE_HEALTH,
-            speed: BASE_SPEED,
+impl CharacterSheet {
+    /// Gives the actor the adjustments of `source`, replacing those it gave before, so
+    /// that a source never stacks with itself.
+    pub fn set_modifiers(
+        &mut self,
+        source: ModifierSource,
+        adjustments: impl IntoIterator<Item = Adjustment>,
+    ) {
+        self.remove_modifiers(&source);
+        self.modifiers
+            .extend(adjustments.into_iter().map(|adjustment| Modifier {
+                source: source.clone(),
+                adjustment,
+            }));
+    }
+
+    pub fn remove_modifiers(&mut self, source: &ModifierSource) {
+        self.modifiers.retain(|modifier| modifier.source != *source);
+    }
+
+    /// Attribute after modifiers.
+    pub fn attribute(&self, attribute: Attribute) -> Int {
+        self.modified_attributes().get(attribute)
+    }
+
+    fn modified_attributes(&self) -> Attributes {
+        let mut attributes = self.attributes;
+        for modifier in self.modifiers.iter() {
+            if let Adjustment::Attribute(attribute, amount) = modifier.adjustment {
+                *attributes.get_mut(attribute) += amount;
+            }
+        }
+        attributes
+    }
+
+    /// Stat after modifiers.
+    pub fn stat(&self, stat: Stat) -> Int {
+        self.breakdown(stat).total
+    }
+
+    pub fn breakdown(&self, stat: Stat) -> StatBreakdown {
+        let base = stat.derive(&self.attributes);
+        let mut steps = vec![];
+
+        let mut attributes = self.attributes;
+        let mut value = base;
+        for modifier in self.modifiers.iter() {
+            if let Adjustment::Attribute(attribute, amount) = modifier.adjustment {
+                *attributes.get_mut(attribute) += amount;
+                let derived = stat.derive(&attributes);
+                if derived != value {
+                    steps.push((modifier.source.clone(), derived - value));
+                    value = derived;
+                }
+            }
+        }
+
+        for modifier in self.modifiers.iter() {
+            match modifier.adjustment {
+                Adjustment::Flat(adjusted, amount) if adjusted == stat => {
+                    steps.push((modifier.source.clone(), amount));
+                    value += amount;
+                }
+                _ => (),
+            }
+        }
+
+        let mut percent = 0;
+        for modifier in self.modifiers.iter() {
+            match modifier.adjustment {
+                Adjustment::Percent(adjusted, amount) if adjusted == stat => {
+                    steps.push((modifier.source.clone(), value * amount / 100));
+                    percent += amount;
+                }
+                _ => (),
+            }
+        }
+        value = value * (100 + percent) / 100;
+
+        StatBreakdown {
+            stat,
+            base,
+            steps,
+            total: value.max(stat.minimum()),
         }
     }
 }
 
-#[derive(Debug, Default, Component)]
+#[derive(Debug, Default, Clone, Component)]
 pub struct CharacterProperties {
+    /// Share of the derived maximum health left.
     pub health: Percentage,
     pub energy: Int, // Available time units
 }
 
-#[derive(Debug, Component)]
+#[derive(Debug, Clone, Component)]
 pub struct Actor {
     pub sheet: CharacterSheet,
     pub properties: CharacterProperties,
@@ -50,12 +129,24 @@ impl Default for Actor {
     }
 }
 
+impl Actor {
+    pub fn take_damage(&mut self, damage: Int) {
+        let lost = 100.0 * damage as Real / self.sheet.stat(Stat::MaxHealth) as Real;
+        self.properties.health -= lost;
+    }
+
+    pub fn is_dead(&self) -> bool {
+        self.properties.health.is_empty()
+    }
+}
+
 #[derive(Bundle)]
 pub struct ActorBundle {
     pub actor: Actor,
     pub position: GridPos,
     pub viewshed: Viewshed,
     pub renderable: Renderable,
+    pub statuses: Statuses,
 }
 
 impl Default for ActorBundle {
@@ -69,6 +160,7 @@ impl Default for ActorBundle {
                 fg: Color::YELLOW,
                 bg: Color::DARK_GRAY,
             },
+            statuses: Default::default(),
         }
     }
 }
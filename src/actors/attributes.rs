@@ -0,0 +1,214 @@
+use serde::Deserialize;
+
+use crate::{
+    actors::{
+        constants::{
+            ATTACK_WINDUP, BASE_ATTRIBUTE, BASE_DAMAGE, BASE_HEALTH, BASE_SPEED, PROJECTILE_DAMAGE,
+        },
+        StatusKind,
+    },
+    core::types::Int,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+pub enum Attribute {
+    Strength,
+    Agility,
+    Perception,
+    Endurance,
+    Willpower,
+}
+
+impl Attribute {
+    pub const ALL: [Attribute; 5] = [
+        Attribute::Strength,
+        Attribute::Agility,
+        Attribute::Perception,
+        Attribute::Endurance,
+        Attribute::Willpower,
+    ];
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Attributes {
+    pub strength: Int,
+    pub agility: Int,
+    pub perception: Int,
+    pub endurance: Int,
+    pub willpower: Int,
+}
+
+impl Default for Attributes {
+    fn default() -> Self {
+        Self {
+            strength: BASE_ATTRIBUTE,
+            agility: BASE_ATTRIBUTE,
+            perception: BASE_ATTRIBUTE,
+            endurance: BASE_ATTRIBUTE,
+            willpower: BASE_ATTRIBUTE,
+        }
+    }
+}
+
+impl Attributes {
+    pub fn get(&self, attribute: Attribute) -> Int {
+        match attribute {
+            Attribute::Strength => self.strength,
+            Attribute::Agility => self.agility,
+            Attribute::Perception => self.perception,
+            Attribute::Endurance => self.endurance,
+            Attribute::Willpower => self.willpower,
+        }
+    }
+
+    pub fn get_mut(&mut self, attribute: Attribute) -> &mut Int {
+        match attribute {
+            Attribute::Strength => &mut self.strength,
+            Attribute::Agility => &mut self.agility,
+            Attribute::Perception => &mut self.perception,
+            Attribute::Endurance => &mut self.endurance,
+            Attribute::Willpower => &mut self.willpower,
+        }
+    }
+}
+
+/// Values derived from an actor's attributes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+pub enum Stat {
+    /// Damage the actor takes from full health to death. Grows with endurance.
+    MaxHealth,
+    /// How quickly the actor acts, with ordinary actors at `BASE_SPEED`. Grows with agility.
+    Speed,
+    /// Tiles the actor sees beyond its own field of view. Grows with perception.
+    SightRange,
+    /// Grows with strength.
+    CarryCapacity,
+    /// Damage of a melee attack. Grows with strength.
+    MeleeDamage,
+    /// Damage of a launched projectile. Grows with agility.
+    RangedDamage,
+    /// Time from telegraphing an attack to striking with it.
+    Windup,
+    /// Shortens statuses put on the actor, with ordinary actors at `BASE_ATTRIBUTE`.
+    /// Grows with willpower.
+    Resolve,
+}
+
+impl Stat {
+    /// Value of the stat for `attributes`, before anything adjusts the stat itself.
+    pub fn derive(&self, attributes: &Attributes) -> Int {
+        let above_base = |attribute| attributes.get(attribute) - BASE_ATTRIBUTE;
+        match self {
+            Stat::MaxHealth => BASE_HEALTH + 10 * above_base(Attribute::Endurance),
+            Stat::Speed => BASE_SPEED + 5 * above_base(Attribute::Agility),
+            Stat::SightRange => above_base(Attribute::Perception) / 2,
+            Stat::CarryCapacity => 5 * attributes.strength,
+            Stat::MeleeDamage => BASE_DAMAGE + 2 * above_base(Attribute::Strength),
+            Stat::RangedDamage => PROJECTILE_DAMAGE + 2 * above_base(Attribute::Agility),
+            Stat::Windup => ATTACK_WINDUP as Int,
+            Stat::Resolve => attributes.willpower,
+        }
+    }
+
+    pub fn minimum(&self) -> Int {
+        match self {
+            Stat::SightRange => Int::MIN,
+            Stat::MaxHealth | Stat::Speed | Stat::Resolve => 1,
+            _ => 0,
+        }
+    }
+}
+
+/// What put a modifier on an actor.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ModifierSource {
+    Equipment(String),
+    Status(StatusKind),
+    Perk(String),
+}
+
+/// Change a modifier makes. Stacking happens in three steps: attribute adjustments
+/// from every modifier are added up and the stats derived from the result, each stat
+/// then gets its flat adjustments added, and is finally scaled once by the sum of its
+/// percentage adjustments.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+pub enum Adjustment {
+    Attribute(Attribute, Int),
+    Flat(Stat, Int),
+    Percent(Stat, Int),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Modifier {
+    pub source: ModifierSource,
+    pub adjustment: Adjustment,
+}
+
+/// How a stat comes to its value, for showing to the player.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StatBreakdown {
+    pub stat: Stat,
+    /// Value derived from the actor's own attributes.
+    pub base: Int,
+    /// Change made by each modifier bearing on the stat, in the order of stacking.
+    /// Percentage changes are rounded, so they may be off by one from the total.
+    pub steps: Vec<(ModifierSource, Int)>,
+    pub total: Int,
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::actors::{CharacterSheet, StatusKind};
+
+    use super::{Adjustment, Attribute, ModifierSource, Stat};
+
+    #[test]
+    fn modifiers_stack_in_order() {
+        let mut sheet = CharacterSheet::default();
+        assert_eq!(100, sheet.stat(Stat::Speed));
+
+        sheet.set_modifiers(
+            ModifierSource::Status(StatusKind::Hasted),
+            vec![Adjustment::Percent(Stat::Speed, 50)],
+        );
+        sheet.set_modifiers(
+            ModifierSource::Equipment("boots".to_string()),
+            vec![
+                Adjustment::Flat(Stat::Speed, 10),
+                Adjustment::Attribute(Attribute::Agility, 2),
+            ],
+        );
+        let breakdown = sheet.breakdown(Stat::Speed);
+        assert_eq!(100, breakdown.base);
+        assert_eq!(
+            vec![
+                (ModifierSource::Equipment("boots".to_string()), 10),
+                (ModifierSource::Equipment("boots".to_string()), 10),
+                (ModifierSource::Status(StatusKind::Hasted), 60),
+            ],
+            breakdown.steps
+        );
+        assert_eq!(180, breakdown.total);
+        assert_eq!(12, sheet.attribute(Attribute::Agility));
+    }
+
+    #[test]
+    fn sources_replace_rather_than_stack_with_themselves() {
+        let mut sheet = CharacterSheet::default();
+        let source = ModifierSource::Equipment("helmet".to_string());
+        for _ in 0..3 {
+            sheet.set_modifiers(
+                source.clone(),
+                vec![Adjustment::Attribute(Attribute::Endurance, 5)],
+            );
+        }
+        assert_eq!(150, sheet.stat(Stat::MaxHealth));
+
+        sheet.set_modifiers(source.clone(), vec![Adjustment::Percent(Stat::Speed, -200)]);
+        assert_eq!(100, sheet.stat(Stat::MaxHealth));
+        assert_eq!(1, sheet.stat(Stat::Speed));
+
+        sheet.remove_modifiers(&source);
+        assert_eq!(100, sheet.stat(Stat::Speed));
+    }
+}
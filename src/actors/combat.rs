@@ -0,0 +1,36 @@
+use bevy::{math::IVec2, prelude::Entity};
+
+use crate::core::types::{Cardinal, Increment, Int};
+
+#[derive(Debug, Clone)]
+pub struct StrikeEvent {
+    pub attacker: Entity,
+    pub positions: Vec<IVec2>,
+    pub damage: Int,
+    /// Direction to knock struck actors towards, for heavy weapons.
+    pub knockback: Option<Cardinal>,
+}
+
+/// Sent to push an actor one tile towards `direction`, whether shoved or knocked back.
+/// The actor loses its current activity and stays busy for `stagger`.
+#[derive(Debug, Clone, Copy)]
+pub struct PushEvent {
+    pub target: Entity,
+    pub direction: Cardinal,
+    pub stagger: Increment,
+}
+
+/// Sent when an actor's health runs out, with whoever struck the blow if anyone did.
+/// Bleeding and poison kill without a killer.
+#[derive(Debug, Clone, Copy)]
+pub struct DeathEvent {
+    pub entity: Entity,
+    pub killer: Option<Entity>,
+}
+
+/// Sent when a monster dies, whatever killed it.
+#[derive(Debug, Clone)]
+pub struct KillEvent {
+    pub killer: Option<Entity>,
+    pub monster: String,
+}
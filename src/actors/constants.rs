@@ -1 +1,47 @@
+use crate::core::types::{Increment, Int, Real};
 
+/// Game time of one step over an ordinary tile.
+pub const MOVE_TIME: Increment = 30;
+
+pub const MOVE_LOUDNESS: Int = 4;
+pub const ATTACK_LOUDNESS: Int = 8;
+pub const WALL_HIT_LOUDNESS: Int = 12;
+pub const SHOVE_LOUDNESS: Int = 6;
+pub const FIRE_LOUDNESS: Int = 3;
+pub const PROJECTILE_IMPACT_LOUDNESS: Int = 6;
+
+/// Every attribute of an ordinary actor.
+pub const BASE_ATTRIBUTE: Int = 10;
+pub const BASE_HEALTH: Int = 100;
+/// Speed at which actions take their ordinary time.
+pub const BASE_SPEED: Int = 100;
+pub const BASE_DAMAGE: Int = 20;
+pub const PROJECTILE_DAMAGE: Int = 15;
+/// Tiles a projectile flies before dropping.
+pub const PROJECTILE_RANGE: Int = 10;
+/// Tiles a projectile crosses per time increment, three times walking speed.
+pub const PROJECTILE_VELOCITY: Real = 0.1;
+
+/// Time from telegraphing an attack to striking, for actors without perks speeding it up.
+pub const ATTACK_WINDUP: Increment = 60;
+
+/// Extra time a shove keeps the shover busy after it lands.
+pub const SHOVE_RECOVERY_TIME: Increment = 30;
+/// Time a pushed actor spends regaining its footing.
+pub const STAGGER_TIME: Increment = 45;
+
+/// Damage multiplier for the player striking a monster unaware of them.
+pub const SNEAK_ATTACK_MULTIPLIER: Int = 3;
+
+/// Time between two rounds of damage from bleeding or poison.
+pub const STATUS_TICK: Increment = 30;
+/// Damage per stack of bleeding each status tick, and each step taken while bleeding.
+pub const BLEED_DAMAGE: Int = 2;
+pub const POISON_DAMAGE: Int = 3;
+pub const MAX_BLEED_STACKS: Int = 3;
+
+/// Experience for killing a monster, per point of its difficulty.
+pub const EXPERIENCE_PER_DIFFICULTY: Int = 25;
+/// Experience the first level up takes. Every further one takes this much more than the last.
+pub const LEVEL_EXPERIENCE: Int = 100;
+pub const ATTRIBUTE_POINTS_PER_LEVEL: Int = 2;
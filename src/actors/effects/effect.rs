@@ -6,3 +6,9 @@ use crate::core::types::Increment;
 pub struct Effect {
     pub time_left: Increment,
 }
+
+/// Marks the tiles an attack is about to strike.
+#[derive(Debug, Clone, Copy, Component)]
+pub struct Telegraph {
+    pub attacker: Entity,
+}
@@ -0,0 +1,124 @@
+use bevy::prelude::*;
+use serde::Deserialize;
+
+use crate::{
+    actors::Inflicts,
+    core::types::{GridPos, Increment, Int, Real},
+    util::helpers::deserialize,
+    world::Renderable,
+};
+
+use super::Effect;
+
+/// How a hazard reaches new tiles.
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+pub enum Spread {
+    /// Stays where it was placed.
+    Still,
+    /// Drifts onto every open neighbouring tile, up to `reach` tiles from where it started.
+    Diffuse { reach: Int },
+    /// Catches on neighbouring flammable tiles, burning them away.
+    Flammable,
+}
+
+impl Default for Spread {
+    fn default() -> Self {
+        Self::Still
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct HazardDefinition {
+    pub kind: String,
+    pub glyph: char,
+    pub fg: (Real, Real, Real),
+    pub bg: (Real, Real, Real),
+    /// Time the hazard lasts on a tile.
+    pub duration: Increment,
+    /// Time between two rounds of damage and spreading.
+    pub interval: Increment,
+    /// Damage to an actor stepping onto the hazard.
+    #[serde(default)]
+    pub enter_damage: Int,
+    /// Damage to every actor standing in the hazard, once per interval.
+    #[serde(default)]
+    pub damage: Int,
+    #[serde(default)]
+    pub spread: Spread,
+    /// Status put on actors entering or standing in the hazard.
+    #[serde(default)]
+    pub inflicts: Option<Inflicts>,
+}
+
+impl HazardDefinition {
+    /// A fresh hazard of this kind on `pos`. Like other effects, it goes away once
+    /// its time is up.
+    pub fn bundle(&self, pos: IVec2) -> HazardBundle {
+        let reach = match self.spread {
+            Spread::Diffuse { reach } => reach,
+            _ => 0,
+        };
+        self.drifted_bundle(pos, reach)
+    }
+
+    /// A hazard of this kind that has drifted onto `pos`, with `reach` tiles left to go.
+    pub fn drifted_bundle(&self, pos: IVec2, reach: Int) -> HazardBundle {
+        HazardBundle {
+            hazard: Hazard {
+                kind: self.kind.clone(),
+                reach,
+                since_tick: 0,
+            },
+            effect: Effect {
+                time_left: self.duration,
+            },
+            position: GridPos(pos),
+            renderable: self.renderable(),
+        }
+    }
+
+    pub fn renderable(&self) -> Renderable {
+        let (r, g, b) = self.fg;
+        let (bg_r, bg_g, bg_b) = self.bg;
+        Renderable {
+            glyph: self.glyph,
+            fg: Color::rgb(r, g, b),
+            bg: Color::rgb(bg_r, bg_g, bg_b),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct HazardDefinitions {
+    pub hazards: Vec<HazardDefinition>,
+}
+
+impl Default for HazardDefinitions {
+    fn default() -> Self {
+        deserialize("assets/data/hazards.ron")
+    }
+}
+
+impl HazardDefinitions {
+    pub fn get(&self, kind: &str) -> Option<&HazardDefinition> {
+        self.hazards.iter().find(|hazard| hazard.kind == kind)
+    }
+}
+
+/// An effect lingering on a tile and harming whoever stands in it.
+#[derive(Debug, Clone, Component)]
+pub struct Hazard {
+    pub kind: String,
+    /// Tiles a diffusing hazard may still drift across.
+    pub reach: Int,
+    /// Time since the hazard last took effect.
+    pub since_tick: Increment,
+}
+
+#[derive(Bundle)]
+pub struct HazardBundle {
+    pub hazard: Hazard,
+    pub effect: Effect,
+    pub position: GridPos,
+    pub renderable: Renderable,
+}
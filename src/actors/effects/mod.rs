@@ -2,3 +2,6 @@ pub mod systems;
 
 mod effect;
 pub use effect::*;
+
+mod hazard;
+pub use hazard::*;
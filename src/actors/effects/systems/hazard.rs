@@ -0,0 +1,285 @@
+use std::collections::HashSet;
+
+use bevy::prelude::*;
+
+use crate::{
+    actors::{
+        effects::{Hazard, HazardDefinitions, Spread},
+        Actor, StatusEvent, StrikeEvent,
+    },
+    core::{
+        types::{GridPos, Increment, Int},
+        TimeIncrementEvent,
+    },
+    util::algorithms::geometry::chessboard_neighbours,
+    world::{TerrainChangedEvent, TileType, WorldMap},
+};
+
+/// Tiles a hazard on `pos` spreads to next.
+fn spread_targets(world_map: &WorldMap, pos: &IVec2, spread: Spread, reach: Int) -> Vec<IVec2> {
+    match spread {
+        Spread::Still => vec![],
+        Spread::Diffuse { .. } if reach <= 0 => vec![],
+        Spread::Diffuse { .. } => chessboard_neighbours(pos)
+            .filter(|neighbour| !world_map.is_blocking(neighbour))
+            .collect(),
+        Spread::Flammable => chessboard_neighbours(pos)
+            .filter(|neighbour| {
+                world_map
+                    .tile_at(neighbour)
+                    .map_or(false, |tile| tile.which().is_flammable())
+            })
+            .collect(),
+    }
+}
+
+/// Once per interval of their kind, hazards harm and afflict the actors standing in
+/// them and spread. Fire burns the flammable tiles it spreads to down to bare floor.
+#[allow(clippy::too_many_arguments)]
+pub fn progress_hazards(
+    mut commands: Commands,
+    mut world_map: ResMut<WorldMap>,
+    definitions: Res<HazardDefinitions>,
+    mut time_events: EventReader<TimeIncrementEvent>,
+    mut hazards: Query<(Entity, &GridPos, &mut Hazard)>,
+    mut strike_writer: EventWriter<StrikeEvent>,
+    mut status_writer: EventWriter<StatusEvent>,
+    mut terrain_writer: EventWriter<TerrainChangedEvent>,
+) {
+    let elapsed: Increment = time_events.iter().map(|event| event.delta_time).sum();
+    if elapsed == 0 {
+        return;
+    }
+
+    // A tile never holds two hazards of the same kind.
+    let mut covered: HashSet<(String, IVec2)> = hazards
+        .iter()
+        .map(|(_, pos, hazard)| (hazard.kind.clone(), pos.0))
+        .collect();
+
+    for (entity, pos, mut hazard) in hazards.iter_mut() {
+        let definition = match definitions.get(&hazard.kind) {
+            Some(definition) => definition,
+            None => continue,
+        };
+        hazard.since_tick += elapsed;
+        let ticks = hazard.since_tick / definition.interval;
+        hazard.since_tick %= definition.interval;
+        if ticks == 0 {
+            continue;
+        }
+
+        let victims: Vec<Entity> = world_map.actors_at(&pos.0).collect();
+        if definition.damage > 0 && !victims.is_empty() {
+            strike_writer.send(StrikeEvent {
+                attacker: entity,
+                positions: vec![pos.0],
+                damage: definition.damage * ticks as Int,
+                knockback: None,
+            });
+        }
+        if let Some(inflicts) = definition.inflicts {
+            for victim in victims {
+                status_writer.send(inflicts.on(victim));
+            }
+        }
+
+        for target in spread_targets(&world_map, &pos.0, definition.spread, hazard.reach) {
+            if !covered.insert((hazard.kind.clone(), target)) {
+                continue;
+            }
+            if definition.spread == Spread::Flammable {
+                if let Some(mut tile) = world_map.tile_at_mut(&target) {
+                    tile.set_type(TileType::FLOOR);
+                }
+                terrain_writer.send(TerrainChangedEvent { position: target });
+            }
+            commands.spawn_bundle(definition.drifted_bundle(target, hazard.reach - 1));
+        }
+    }
+}
+
+/// Harms and afflicts actors as they step onto a hazard, or are pushed onto one.
+pub fn trigger_hazards(
+    world_map: Res<WorldMap>,
+    definitions: Res<HazardDefinitions>,
+    arrivals: Query<(Entity, &GridPos), (With<Actor>, Changed<GridPos>)>,
+    hazards: Query<&Hazard>,
+    mut strike_writer: EventWriter<StrikeEvent>,
+    mut status_writer: EventWriter<StatusEvent>,
+) {
+    for (entity, pos) in arrivals.iter() {
+        for occupant in world_map.occupants_at(&pos.0) {
+            let definition = match hazards
+                .get(occupant.entity)
+                .ok()
+                .and_then(|hazard| definitions.get(&hazard.kind))
+            {
+                Some(definition) => definition,
+                None => continue,
+            };
+            if definition.enter_damage > 0 {
+                strike_writer.send(StrikeEvent {
+                    attacker: occupant.entity,
+                    positions: vec![pos.0],
+                    damage: definition.enter_damage,
+                    knockback: None,
+                });
+            }
+            if let Some(inflicts) = definition.inflicts {
+                status_writer.send(inflicts.on(entity));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::prelude::*;
+
+    use crate::{
+        actors::{
+            effects::{Hazard, HazardDefinitions},
+            ActorBundle, StatusEvent, StrikeEvent,
+        },
+        core::{
+            types::{GridPos, Increment},
+            TimeIncrementEvent,
+        },
+        test::helpers::{
+            self,
+            world::{sent_events, world_from_ascii_layout},
+        },
+        world::{systems::update_spatial_index, TerrainChangedEvent, TileType, WorldMap},
+    };
+
+    use super::{progress_hazards, trigger_hazards};
+
+    fn hazard_world(layout: &str, kind: &str) -> (World, IVec2) {
+        let (mut world, origin) = world_from_ascii_layout(layout);
+        world.insert_resource(Events::<TimeIncrementEvent>::default());
+        world.insert_resource(Events::<StrikeEvent>::default());
+        world.insert_resource(Events::<StatusEvent>::default());
+        world.insert_resource(Events::<TerrainChangedEvent>::default());
+        let definitions = HazardDefinitions::default();
+        world
+            .spawn()
+            .insert_bundle(definitions.get(kind).unwrap().bundle(origin));
+        world.insert_resource(definitions);
+        SystemStage::single(update_spatial_index.system()).run(&mut world);
+        (world, origin)
+    }
+
+    /// Lets time pass, then indexes wherever the hazards spread to.
+    fn pass_time(world: &mut World, stage: &mut SystemStage, delta_time: Increment) {
+        helpers::world::pass_time(world, stage, delta_time);
+        SystemStage::single(update_spatial_index.system()).run(world);
+    }
+
+    fn hazard_tiles(world: &mut World) -> Vec<IVec2> {
+        let mut tiles: Vec<IVec2> = world
+            .query::<(&GridPos, &Hazard)>()
+            .iter(world)
+            .map(|(pos, _)| pos.0)
+            .collect();
+        tiles.sort_by_key(|pos| (pos.y, pos.x));
+        tiles
+    }
+
+    #[test]
+    fn gas_drifts_as_far_as_it_reaches() {
+        let (mut world, origin) = hazard_world(
+            "###########
+             #.........#
+             #....@....#
+             #.........#
+             ###########",
+            "poison gas",
+        );
+        let mut stage = SystemStage::single(progress_hazards.system());
+
+        pass_time(&mut world, &mut stage, 29);
+        assert_eq!(vec![origin], hazard_tiles(&mut world));
+
+        pass_time(&mut world, &mut stage, 1);
+        assert_eq!(9, hazard_tiles(&mut world).len());
+
+        for _ in 0..5 {
+            pass_time(&mut world, &mut stage, 30);
+        }
+        let tiles = hazard_tiles(&mut world);
+        assert_eq!(3 * 7, tiles.len());
+        assert!(tiles.iter().all(|pos| (pos.x - origin.x).abs() <= 3));
+    }
+
+    #[test]
+    fn fire_burns_through_flammable_tiles_only() {
+        let (mut world, origin) = hazard_world(
+            "#######
+             #@....#
+             #######",
+            "fire",
+        );
+        {
+            let mut world_map = world.get_resource_mut::<WorldMap>().unwrap();
+            let bush = TileType::named("bush").unwrap();
+            for x in 2..=3 {
+                world_map
+                    .tile_at_mut(&IVec2::new(x, 1))
+                    .unwrap()
+                    .set_type(bush);
+            }
+        }
+        let mut stage = SystemStage::single(progress_hazards.system());
+
+        for _ in 0..4 {
+            pass_time(&mut world, &mut stage, 30);
+        }
+        assert_eq!(
+            vec![origin, IVec2::new(2, 1), IVec2::new(3, 1)],
+            hazard_tiles(&mut world)
+        );
+        let world_map = world.get_resource::<WorldMap>().unwrap();
+        assert_eq!(
+            TileType::FLOOR,
+            world_map.tile_at(&IVec2::new(2, 1)).unwrap().which()
+        );
+    }
+
+    #[test]
+    fn actors_are_hurt_entering_and_standing_in_hazards() {
+        let (mut world, origin) = hazard_world(
+            "#####
+             #.@.#
+             #####",
+            "fire",
+        );
+        let actor = world
+            .spawn()
+            .insert_bundle(ActorBundle {
+                position: GridPos(origin - IVec2::X),
+                ..Default::default()
+            })
+            .id();
+        let mut trigger = SystemStage::single(trigger_hazards.system());
+        trigger.run(&mut world);
+        assert!(sent_events::<StrikeEvent>(&world).is_empty());
+
+        world.get_mut::<GridPos>(actor).unwrap().0 = origin;
+        SystemStage::single(update_spatial_index.system()).run(&mut world);
+        trigger.run(&mut world);
+        let entered = sent_events::<StrikeEvent>(&world);
+        assert_eq!(1, entered.len());
+        assert_eq!(10, entered[0].damage);
+
+        pass_time(
+            &mut world,
+            &mut SystemStage::single(progress_hazards.system()),
+            60,
+        );
+        let strikes = sent_events::<StrikeEvent>(&world);
+        assert_eq!(2, strikes.len());
+        assert_eq!(20, strikes[1].damage);
+        assert_eq!(vec![origin], strikes[1].positions);
+    }
+}
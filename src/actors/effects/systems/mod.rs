@@ -4,6 +4,9 @@ use crate::core::TimeIncrementEvent;
 
 use super::Effect;
 
+mod hazard;
+pub use hazard::*;
+
 pub fn progress_effects(
     mut commands: Commands,
     mut time_events: EventReader<TimeIncrementEvent>,
@@ -1,16 +1,34 @@
 mod activities;
+pub use activities::systems::{
+    attack_pattern, compute_next_position, do_move, rotate_facing, sweep_pattern,
+};
 pub use activities::*;
 
 mod actor;
 pub use actor::*;
 
+mod attributes;
+pub use attributes::*;
+
+mod combat;
+pub use combat::{DeathEvent, KillEvent, PushEvent, StrikeEvent};
+
 pub mod effects;
 
 mod player;
 pub use player::Player;
 
+mod progression;
+pub use progression::*;
+
+mod projectile;
+pub use projectile::{projectile_glyph, Projectile};
+
 pub mod constants;
 
+mod status;
+pub use status::*;
+
 mod weapon;
 pub use weapon::*;
 
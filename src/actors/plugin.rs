@@ -2,31 +2,101 @@ use bevy::prelude::*;
 
 use super::{
     activities::systems::{do_activities, progress_activities},
-    effects::systems::progress_effects,
-    systems::handle_player_input,
+    effects::{
+        systems::{progress_effects, progress_hazards, trigger_hazards},
+        HazardDefinitions,
+    },
+    systems::{
+        advance_projectiles, apply_statuses, award_experience, bleed_on_move, equip_weapons,
+        handle_level_up_input, handle_player_input, open_level_up, progress_statuses,
+        resolve_deaths, resolve_pushes, resolve_strikes, scale_activity_time, update_sight_range,
+    },
+    DeathEvent, KillEvent, PerkTree, PushEvent, StatusEvent, StrikeEvent,
 };
 use crate::AppState;
 
 #[derive(Debug, Hash, PartialEq, Eq, Clone, SystemLabel)]
 pub enum ActorSystems {
+    /// Adjusts newly started activities before any time passes on them.
+    Pace,
     Action,
+    Strike,
+    Death,
+    Push,
 }
 
 pub struct ActorPlugin;
 
 impl Plugin for ActorPlugin {
     fn build(&self, app: &mut App) {
-        app.add_system_set(SystemSet::on_update(AppState::Paused).with_system(handle_player_input));
+        app.init_resource::<HazardDefinitions>()
+            .init_resource::<PerkTree>()
+            .add_event::<StrikeEvent>()
+            .add_event::<PushEvent>()
+            .add_event::<StatusEvent>()
+            .add_event::<DeathEvent>()
+            .add_event::<KillEvent>()
+            .add_system_set(
+                SystemSet::on_update(AppState::Paused)
+                    .with_system(handle_player_input)
+                    .with_system(open_level_up),
+            )
+            .add_system_set(
+                SystemSet::on_update(AppState::LevelUp).with_system(handle_level_up_input),
+            );
         app.add_system_set(
             SystemSet::on_update(AppState::Running)
+                .label(ActorSystems::Pace)
+                .before(ActorSystems::Action)
+                .with_system(scale_activity_time.system()),
+        )
+        .add_system_set(
+            SystemSet::on_update(AppState::Running)
+                .after(ActorSystems::Pace)
                 .before(ActorSystems::Action)
                 .with_system(progress_activities.system())
-                .with_system(progress_effects.system()),
+                .with_system(progress_effects.system())
+                .with_system(progress_hazards.system())
+                .with_system(progress_statuses.system())
+                .with_system(equip_weapons.system())
+                .with_system(update_sight_range.system()),
         )
         .add_system_set(
             SystemSet::on_update(AppState::Running)
                 .label(ActorSystems::Action)
-                .with_system(do_activities.system()),
+                .with_system(do_activities.system())
+                .with_system(advance_projectiles.system()),
+        )
+        .add_system_set(
+            SystemSet::on_update(AppState::Running)
+                .after(ActorSystems::Action)
+                .before(ActorSystems::Strike)
+                .with_system(trigger_hazards.system())
+                .with_system(bleed_on_move.system()),
+        )
+        .add_system_set(
+            SystemSet::on_update(AppState::Running)
+                .after(ActorSystems::Action)
+                .label(ActorSystems::Strike)
+                .with_system(resolve_strikes.system()),
+        )
+        .add_system_set(
+            SystemSet::on_update(AppState::Running)
+                .after(ActorSystems::Strike)
+                .label(ActorSystems::Death)
+                .with_system(resolve_deaths.system()),
+        )
+        .add_system_set(
+            SystemSet::on_update(AppState::Running)
+                .after(ActorSystems::Death)
+                .label(ActorSystems::Push)
+                .with_system(resolve_pushes.system())
+                .with_system(award_experience.system()),
+        )
+        .add_system_set(
+            SystemSet::on_update(AppState::Running)
+                .after(ActorSystems::Push)
+                .with_system(apply_statuses.system()),
         );
     }
 }
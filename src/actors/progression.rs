@@ -0,0 +1,159 @@
+use bevy::prelude::Component;
+use serde::Deserialize;
+
+use crate::{
+    actors::{
+        constants::{ATTRIBUTE_POINTS_PER_LEVEL, LEVEL_EXPERIENCE},
+        Action, Adjustment,
+    },
+    core::types::Int,
+    util::helpers::deserialize,
+};
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct PerkDefinition {
+    pub name: String,
+    pub description: String,
+    /// Perks that have to be taken first.
+    #[serde(default)]
+    pub requires: Vec<String>,
+    #[serde(default)]
+    pub min_level: Int,
+    #[serde(default)]
+    pub modifiers: Vec<Adjustment>,
+    /// Action only actors with the perk can take.
+    #[serde(default)]
+    pub unlocks: Option<Action>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct PerkTree {
+    pub perks: Vec<PerkDefinition>,
+}
+
+impl Default for PerkTree {
+    fn default() -> Self {
+        deserialize("assets/data/perks.ron")
+    }
+}
+
+impl PerkTree {
+    /// Perks `experience` may pick next: not yet taken, with every requirement met.
+    pub fn available<'a>(
+        &'a self,
+        experience: &'a Experience,
+    ) -> impl Iterator<Item = &'a PerkDefinition> {
+        self.perks.iter().filter(move |perk| {
+            !experience.has_perk(&perk.name)
+                && experience.level >= perk.min_level
+                && perk.requires.iter().all(|name| experience.has_perk(name))
+        })
+    }
+
+    /// Whether an actor with `experience` can take `action`. Actors without any
+    /// experience only take actions no perk unlocks.
+    pub fn allows(&self, action: Action, experience: Option<&Experience>) -> bool {
+        self.perks
+            .iter()
+            .filter(|perk| perk.unlocks == Some(action))
+            .all(|perk| experience.map_or(false, |experience| experience.has_perk(&perk.name)))
+    }
+}
+
+/// Experience gathered by an actor and what it has to show for it.
+#[derive(Debug, Clone, Component)]
+pub struct Experience {
+    pub points: Int,
+    pub level: Int,
+    pub attribute_points: Int,
+    pub perk_points: Int,
+    pub perks: Vec<String>,
+}
+
+impl Default for Experience {
+    fn default() -> Self {
+        Self {
+            points: 0,
+            level: 1,
+            attribute_points: 0,
+            perk_points: 0,
+            perks: vec![],
+        }
+    }
+}
+
+impl Experience {
+    /// Total experience needed to reach the level after the current one. Each level
+    /// takes `LEVEL_EXPERIENCE` more than the one before.
+    pub fn next_level_at(&self) -> Int {
+        LEVEL_EXPERIENCE * self.level * (self.level + 1) / 2
+    }
+
+    /// Adds experience, levelling up as often as it allows. Returns the levels gained.
+    pub fn gain(&mut self, points: Int) -> Int {
+        self.points += points;
+        let mut gained = 0;
+        while self.points >= self.next_level_at() {
+            self.level += 1;
+            self.attribute_points += ATTRIBUTE_POINTS_PER_LEVEL;
+            self.perk_points += 1;
+            gained += 1;
+        }
+        gained
+    }
+
+    pub fn has_perk(&self, name: &str) -> bool {
+        self.perks.iter().any(|perk| perk == name)
+    }
+
+    /// Whether there are points left to spend on anything.
+    pub fn has_choices(&self, perk_tree: &PerkTree) -> bool {
+        self.attribute_points > 0
+            || (self.perk_points > 0 && perk_tree.available(self).next().is_some())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::actors::Action;
+
+    use super::{Experience, PerkTree};
+
+    #[test]
+    fn levels_come_further_apart() {
+        let mut experience = Experience::default();
+        assert_eq!(0, experience.gain(99));
+        assert_eq!(1, experience.gain(1));
+        assert_eq!(2, experience.level);
+        assert_eq!(2, experience.gain(500));
+        assert_eq!(4, experience.level);
+        assert_eq!(6, experience.attribute_points);
+        assert_eq!(3, experience.perk_points);
+    }
+
+    #[test]
+    fn perks_open_up_the_tree() {
+        let perk_tree = PerkTree::default();
+        let mut experience = Experience::default();
+        assert!(!perk_tree.allows(Action::InitiateSweep, Some(&experience)));
+        assert!(perk_tree.allows(Action::InitiateAttack, None));
+
+        let sweeper = perk_tree
+            .perks
+            .iter()
+            .find(|perk| perk.unlocks == Some(Action::InitiateSweep))
+            .unwrap();
+        assert!(!perk_tree
+            .available(&experience)
+            .any(|perk| perk.name == sweeper.name));
+
+        experience.level = sweeper.min_level;
+        experience.perks = sweeper.requires.clone();
+        assert!(perk_tree
+            .available(&experience)
+            .any(|perk| perk.name == sweeper.name));
+
+        experience.perks.push(sweeper.name.clone());
+        assert!(perk_tree.allows(Action::InitiateSweep, Some(&experience)));
+    }
+}
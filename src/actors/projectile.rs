@@ -0,0 +1,54 @@
+use bevy::{math::IVec2, prelude::*};
+
+use crate::{
+    core::types::{Cardinal, Int, Real},
+    util::algorithms::{geometry::chessboard_distance, line::symmetric_line},
+};
+
+/// Something flying through the air, advanced tile by tile as game time passes.
+#[derive(Debug, Clone, Component)]
+pub struct Projectile {
+    pub shooter: Entity,
+    /// Where it was launched from, to judge the cover of whatever it hits.
+    pub origin: IVec2,
+    /// Tiles still ahead, nearest first.
+    pub path: Vec<IVec2>,
+    /// Tiles travelled per time increment.
+    pub velocity: Real,
+    /// Share of the way to the next tile already travelled.
+    pub progress: Real,
+    pub damage: Int,
+}
+
+impl Projectile {
+    /// Flies from `origin` towards `target` and on until `range` tiles away.
+    pub fn aimed(
+        shooter: Entity,
+        origin: IVec2,
+        target: IVec2,
+        range: Int,
+        velocity: Real,
+        damage: Int,
+    ) -> Self {
+        let distance = chessboard_distance(&origin, &target).max(1);
+        let end = origin + (target - origin) * range / distance;
+        Self {
+            shooter,
+            origin,
+            path: symmetric_line(&origin, &end).into_iter().skip(1).collect(),
+            velocity,
+            progress: 0.0,
+            damage,
+        }
+    }
+}
+
+/// Glyph of a projectile flying towards `cardinal`.
+pub fn projectile_glyph(cardinal: Cardinal) -> char {
+    match cardinal {
+        Cardinal::North | Cardinal::South => '|',
+        Cardinal::East | Cardinal::West => '-',
+        Cardinal::NorthEast | Cardinal::SouthWest => '/',
+        Cardinal::NorthWest | Cardinal::SouthEast => '\\',
+    }
+}
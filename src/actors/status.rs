@@ -0,0 +1,205 @@
+use bevy::prelude::*;
+use serde::Deserialize;
+
+use crate::{
+    actors::{
+        constants::{BLEED_DAMAGE, MAX_BLEED_STACKS, POISON_DAMAGE},
+        Adjustment, Stat,
+    },
+    core::types::{Increment, Int},
+    world::ViewModifier,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+pub enum StatusKind {
+    /// Can't act, and sees only straight ahead.
+    Stunned,
+    /// Loses health over time and whenever moving.
+    Bleeding,
+    /// Has a third less speed.
+    Slowed,
+    /// Has double speed.
+    Hasted,
+    /// Loses health over time and hits more weakly.
+    Poisoned,
+}
+
+/// How a status combines with one of the same kind the actor already has.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Stacking {
+    /// Lasts for the longer of the two durations.
+    Refresh,
+    /// Adds a stack, up to a maximum, and lasts for the longer duration.
+    Intensify(Int),
+    /// Lasts for both durations added together.
+    Extend,
+}
+
+impl StatusKind {
+    pub fn stacking(&self) -> Stacking {
+        match self {
+            StatusKind::Bleeding => Stacking::Intensify(MAX_BLEED_STACKS),
+            StatusKind::Poisoned => Stacking::Extend,
+            _ => Stacking::Refresh,
+        }
+    }
+
+    /// Status this one cancels out when applied.
+    pub fn opposite(&self) -> Option<StatusKind> {
+        match self {
+            StatusKind::Slowed => Some(StatusKind::Hasted),
+            StatusKind::Hasted => Some(StatusKind::Slowed),
+            _ => None,
+        }
+    }
+
+    /// Damage dealt by a single stack every status tick.
+    pub fn damage_per_tick(&self) -> Int {
+        match self {
+            StatusKind::Bleeding => BLEED_DAMAGE,
+            StatusKind::Poisoned => POISON_DAMAGE,
+            _ => 0,
+        }
+    }
+
+    /// Changes to the stats of actors with the status.
+    pub fn adjustments(&self) -> Vec<Adjustment> {
+        match self {
+            StatusKind::Slowed => vec![Adjustment::Percent(Stat::Speed, -34)],
+            StatusKind::Hasted => vec![Adjustment::Percent(Stat::Speed, 100)],
+            StatusKind::Poisoned => vec![
+                Adjustment::Percent(Stat::MeleeDamage, -25),
+                Adjustment::Percent(Stat::RangedDamage, -25),
+            ],
+            _ => vec![],
+        }
+    }
+
+    pub fn view_modifier(&self) -> Option<ViewModifier> {
+        match self {
+            StatusKind::Stunned => Some(ViewModifier::TunnelVision),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Status {
+    pub kind: StatusKind,
+    pub time_left: Increment,
+    pub stacks: Int,
+    /// Time since the status last ticked.
+    pub since_tick: Increment,
+}
+
+/// Status effects currently on an actor.
+#[derive(Debug, Default, Clone, Component)]
+pub struct Statuses {
+    pub active: Vec<Status>,
+}
+
+impl Statuses {
+    /// Adds a status for `duration`, following its stacking rule. Returns whether the
+    /// actor didn't have the status yet.
+    pub fn apply(&mut self, kind: StatusKind, duration: Increment) -> bool {
+        if let Some(opposite) = kind.opposite() {
+            self.active.retain(|status| status.kind != opposite);
+        }
+        match self.active.iter_mut().find(|status| status.kind == kind) {
+            Some(status) => match kind.stacking() {
+                Stacking::Refresh => status.time_left = status.time_left.max(duration),
+                Stacking::Intensify(max_stacks) => {
+                    status.stacks = (status.stacks + 1).min(max_stacks);
+                    status.time_left = status.time_left.max(duration);
+                }
+                Stacking::Extend => status.time_left += duration,
+            },
+            None => {
+                self.active.push(Status {
+                    kind,
+                    time_left: duration,
+                    stacks: 1,
+                    since_tick: 0,
+                });
+                return true;
+            }
+        }
+        false
+    }
+
+    pub fn has(&self, kind: StatusKind) -> bool {
+        self.active.iter().any(|status| status.kind == kind)
+    }
+
+    pub fn stacks(&self, kind: StatusKind) -> Int {
+        self.active
+            .iter()
+            .find(|status| status.kind == kind)
+            .map_or(0, |status| status.stacks)
+    }
+
+    pub fn time_left(&self, kind: StatusKind) -> Increment {
+        self.active
+            .iter()
+            .find(|status| status.kind == kind)
+            .map_or(0, |status| status.time_left)
+    }
+}
+
+/// Status an actor's hits or a hazard puts on whoever they touch.
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize, Component)]
+pub struct Inflicts {
+    pub kind: StatusKind,
+    pub duration: Increment,
+}
+
+impl Inflicts {
+    pub fn on(&self, target: Entity) -> StatusEvent {
+        StatusEvent {
+            target,
+            kind: self.kind,
+            duration: self.duration,
+        }
+    }
+}
+
+/// Sent to put a status on an actor.
+#[derive(Debug, Clone, Copy)]
+pub struct StatusEvent {
+    pub target: Entity,
+    pub kind: StatusKind,
+    pub duration: Increment,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{StatusKind, Statuses};
+
+    #[test]
+    fn stacking_rules() {
+        let mut statuses = Statuses::default();
+
+        assert!(statuses.apply(StatusKind::Stunned, 60));
+        assert!(!statuses.apply(StatusKind::Stunned, 30));
+        assert_eq!(60, statuses.time_left(StatusKind::Stunned));
+
+        for _ in 0..5 {
+            statuses.apply(StatusKind::Bleeding, 90);
+        }
+        assert_eq!(3, statuses.stacks(StatusKind::Bleeding));
+
+        statuses.apply(StatusKind::Poisoned, 100);
+        statuses.apply(StatusKind::Poisoned, 50);
+        assert_eq!(1, statuses.stacks(StatusKind::Poisoned));
+        assert_eq!(150, statuses.time_left(StatusKind::Poisoned));
+    }
+
+    #[test]
+    fn haste_and_slow_cancel_out() {
+        let mut statuses = Statuses::default();
+        statuses.apply(StatusKind::Slowed, 90);
+        statuses.apply(StatusKind::Hasted, 90);
+        assert!(!statuses.has(StatusKind::Slowed));
+        assert!(statuses.has(StatusKind::Hasted));
+    }
+}
@@ -0,0 +1,303 @@
+use std::collections::HashSet;
+
+use bevy::prelude::*;
+
+use crate::{
+    actors::{
+        constants::{SNEAK_ATTACK_MULTIPLIER, STAGGER_TIME},
+        Actor, DeathEvent, Inflicts, KillEvent, Player, PushEvent, StatusEvent, StrikeEvent,
+    },
+    ai::{Awareness, AwarenessState, Faction, FactionRelations, Monster, Relation},
+    core::types::GridPos,
+    world::WorldMap,
+};
+
+/// Applies strikes to the actors standing on the struck tiles. The player deals
+/// sneak attack damage to monsters unaware of them, which alerts the monster.
+/// Whatever the attacker inflicts sticks to survivors.
+#[allow(clippy::too_many_arguments)]
+pub fn resolve_strikes(
+    relations: Res<FactionRelations>,
+    world_map: Res<WorldMap>,
+    mut strike_events: EventReader<StrikeEvent>,
+    mut push_writer: EventWriter<PushEvent>,
+    mut status_writer: EventWriter<StatusEvent>,
+    mut death_writer: EventWriter<DeathEvent>,
+    mut targets: Query<(
+        &mut Actor,
+        Option<&Faction>,
+        Option<&Player>,
+        Option<&mut Awareness>,
+    )>,
+    positions: Query<&GridPos>,
+    inflictions: Query<&Inflicts>,
+) {
+    for strike in strike_events.iter() {
+        // Hazards strike too, but only actors get the blame for a kill.
+        let (killer, attacker_faction, by_player) = match targets.get(strike.attacker) {
+            Ok((_, faction, player, _)) => {
+                (Some(strike.attacker), faction.cloned(), player.is_some())
+            }
+            Err(_) => (None, None, false),
+        };
+        let attacker_pos = positions.get(strike.attacker).ok().map(|pos| pos.0);
+        let inflicts = inflictions.get(strike.attacker).ok();
+
+        for (_, occupant) in world_map.occupants_in(&strike.positions) {
+            let entity = occupant.entity;
+            if entity == strike.attacker || !occupant.is_actor {
+                continue;
+            }
+            let (mut actor, faction, _, awareness) = match targets.get_mut(entity) {
+                Ok(target) => target,
+                Err(_) => continue,
+            };
+            // Despawning waits for the end of the stage, so the dead still show up here.
+            if actor.is_dead() {
+                continue;
+            }
+            if let (Some(attacker_faction), Some(faction)) = (&attacker_faction, faction) {
+                if relations.relation(attacker_faction, faction) == Relation::Allied {
+                    continue;
+                }
+            }
+
+            let mut damage = strike.damage;
+            if let (Some(mut awareness), Some(attacker_pos)) = (awareness, attacker_pos) {
+                if by_player {
+                    if awareness.state() == AwarenessState::Unaware {
+                        damage *= SNEAK_ATTACK_MULTIPLIER;
+                    }
+                    awareness.alert(strike.attacker, attacker_pos);
+                }
+            }
+
+            actor.take_damage(damage);
+            if actor.is_dead() {
+                death_writer.send(DeathEvent { entity, killer });
+                continue;
+            }
+            if let Some(direction) = strike.knockback {
+                push_writer.send(PushEvent {
+                    target: entity,
+                    direction,
+                    stagger: STAGGER_TIME,
+                });
+            }
+            if let Some(inflicts) = inflicts {
+                status_writer.send(inflicts.on(entity));
+            }
+        }
+    }
+}
+
+/// Despawns whoever died, other than the player, and reports the monsters among them.
+/// Every cause of death ends up here, so that each kill is reported once.
+pub fn resolve_deaths(
+    mut commands: Commands,
+    mut death_events: EventReader<DeathEvent>,
+    mut kill_writer: EventWriter<KillEvent>,
+    dead: Query<Option<&Monster>, (With<Actor>, Without<Player>)>,
+) {
+    let mut despawned: HashSet<Entity> = HashSet::new();
+    for death in death_events.iter() {
+        if !despawned.insert(death.entity) {
+            continue;
+        }
+        let monster = match dead.get(death.entity) {
+            Ok(monster) => monster,
+            Err(_) => continue,
+        };
+        commands.entity(death.entity).despawn_recursive();
+        if let Some(monster) = monster {
+            kill_writer.send(KillEvent {
+                killer: death.killer,
+                monster: monster.kind.clone(),
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::prelude::*;
+
+    use crate::{
+        actors::{
+            Actor, ActorBundle, DeathEvent, KillEvent, Player, PushEvent, StatusEvent, StrikeEvent,
+        },
+        ai::{Awareness, AwarenessState, Faction, FactionRelations, Monster},
+        core::types::{Cardinal, GridPos},
+        test::helpers::world::{sent_events, world_from_ascii_layout},
+        world::systems::update_spatial_index,
+    };
+
+    use super::{resolve_deaths, resolve_strikes};
+
+    fn strike(world: &mut World, attacker: Entity, at: IVec2) {
+        let mut events = Events::<StrikeEvent>::default();
+        events.send(StrikeEvent {
+            attacker,
+            positions: vec![at],
+            damage: 10,
+            knockback: None,
+        });
+        world.insert_resource(events);
+        SystemStage::single(resolve_strikes.system()).run(world);
+    }
+
+    /// A player with room for a monster right next to them.
+    fn arena() -> (World, Entity, IVec2) {
+        let (mut world, start) = world_from_ascii_layout(
+            "#####
+             #@..#
+             #####",
+        );
+        world.insert_resource(FactionRelations::default());
+        world.insert_resource(Events::<PushEvent>::default());
+        world.insert_resource(Events::<StatusEvent>::default());
+        world.insert_resource(Events::<DeathEvent>::default());
+        world.insert_resource(Events::<KillEvent>::default());
+        let player = world
+            .spawn()
+            .insert(Player)
+            .insert_bundle(ActorBundle {
+                position: GridPos(start),
+                ..Default::default()
+            })
+            .id();
+        (world, player, start + IVec2::X)
+    }
+
+    #[test]
+    fn sneak_attacks_hit_harder_and_alert() {
+        let (mut world, player, monster_pos) = arena();
+        let monster = world
+            .spawn()
+            .insert(Awareness::default())
+            .insert_bundle(ActorBundle {
+                position: GridPos(monster_pos),
+                ..Default::default()
+            })
+            .id();
+        SystemStage::single(update_spatial_index.system()).run(&mut world);
+
+        strike(&mut world, player, monster_pos);
+        let health = |world: &World| world.get::<Actor>(monster).unwrap().properties.health;
+        assert_eq!(70.0, health(&world).value());
+        let awareness = world.get::<Awareness>(monster).unwrap();
+        assert_eq!(AwarenessState::Alerted, awareness.state());
+        assert_eq!(
+            world.get::<GridPos>(player).map(|pos| pos.0),
+            awareness.last_sensed
+        );
+
+        strike(&mut world, player, monster_pos);
+        assert_eq!(60.0, health(&world).value());
+    }
+
+    fn spawn_goblin(world: &mut World, pos: IVec2) -> Entity {
+        world
+            .spawn()
+            .insert(Faction("goblins".to_string()))
+            .insert_bundle(ActorBundle {
+                position: GridPos(pos),
+                ..Default::default()
+            })
+            .id()
+    }
+
+    #[test]
+    fn sweeps_spare_allies() {
+        let (mut world, player, ally_pos) = arena();
+        world
+            .entity_mut(player)
+            .insert(Faction("player".to_string()));
+        let attacker = spawn_goblin(&mut world, ally_pos + IVec2::X);
+        let ally = spawn_goblin(&mut world, ally_pos);
+        SystemStage::single(update_spatial_index.system()).run(&mut world);
+
+        let player_pos = world.get::<GridPos>(player).unwrap().0;
+        let mut events = Events::<StrikeEvent>::default();
+        events.send(StrikeEvent {
+            attacker,
+            positions: vec![ally_pos, player_pos],
+            damage: 10,
+            knockback: Some(Cardinal::West),
+        });
+        world.insert_resource(events);
+        SystemStage::single(resolve_strikes.system()).run(&mut world);
+
+        let health = |entity| {
+            world
+                .get::<Actor>(entity)
+                .unwrap()
+                .properties
+                .health
+                .value()
+        };
+        assert_eq!(100.0, health(ally));
+        assert_eq!(90.0, health(player));
+        let pushed: Vec<Entity> = sent_events::<PushEvent>(&world)
+            .iter()
+            .map(|push| push.target)
+            .collect();
+        assert_eq!(vec![player], pushed);
+    }
+
+    #[test]
+    fn each_kill_is_reported_once() {
+        let (mut world, player, monster_pos) = arena();
+        world
+            .spawn()
+            .insert(Monster {
+                kind: "goblin".to_string(),
+            })
+            .insert_bundle(ActorBundle {
+                position: GridPos(monster_pos),
+                ..Default::default()
+            });
+        SystemStage::single(update_spatial_index.system()).run(&mut world);
+
+        let mut events = Events::<StrikeEvent>::default();
+        for _ in 0..2 {
+            events.send(StrikeEvent {
+                attacker: player,
+                positions: vec![monster_pos],
+                damage: 100,
+                knockback: None,
+            });
+        }
+        world.insert_resource(events);
+        let mut stage = SystemStage::parallel();
+        stage
+            .add_system(resolve_strikes.system().label("strikes"))
+            .add_system(resolve_deaths.system().after("strikes"));
+        stage.run(&mut world);
+
+        let kills = sent_events::<KillEvent>(&world);
+        assert_eq!(1, kills.len());
+        assert_eq!(Some(player), kills[0].killer);
+    }
+
+    #[test]
+    fn hazards_kill_without_a_killer() {
+        let (mut world, _, monster_pos) = arena();
+        let hazard = world.spawn().insert(GridPos(monster_pos)).id();
+        let monster = world
+            .spawn()
+            .insert_bundle(ActorBundle {
+                position: GridPos(monster_pos),
+                ..Default::default()
+            })
+            .id();
+        world.get_mut::<Actor>(monster).unwrap().take_damage(95);
+        SystemStage::single(update_spatial_index.system()).run(&mut world);
+
+        strike(&mut world, hazard, monster_pos);
+        let deaths = sent_events::<DeathEvent>(&world);
+        assert_eq!(1, deaths.len());
+        assert_eq!(monster, deaths[0].entity);
+        assert_eq!(None, deaths[0].killer);
+    }
+}
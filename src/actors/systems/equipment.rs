@@ -0,0 +1,18 @@
+use bevy::prelude::*;
+
+use crate::actors::{Actor, ModifierSource, Weapon};
+
+/// Passes the adjustments of newly wielded or changed weapons on to their wielders.
+pub fn equip_weapons(
+    weapons: Query<(&Parent, &Weapon), Changed<Weapon>>,
+    mut wielders: Query<&mut Actor>,
+) {
+    for (wielder, weapon) in weapons.iter() {
+        if let Ok(mut actor) = wielders.get_mut(wielder.0) {
+            actor.sheet.set_modifiers(
+                ModifierSource::Equipment("weapon".to_string()),
+                weapon.modifiers.iter().copied(),
+            );
+        }
+    }
+}
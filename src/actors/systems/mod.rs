@@ -1,2 +1,20 @@
+mod combat;
+pub use combat::*;
+
+mod equipment;
+pub use equipment::*;
+
 mod player;
 pub use player::*;
+
+mod progression;
+pub use progression::*;
+
+mod projectile;
+pub use projectile::*;
+
+mod push;
+pub use push::*;
+
+mod status;
+pub use status::*;
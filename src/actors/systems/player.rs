@@ -4,22 +4,30 @@ use bevy::{
 };
 
 use crate::{
-    actors::{Action, Activity, Player},
+    actors::{Action, Activity, Experience, PerkTree, Player},
     settings::PlayerSettings,
     AppState,
 };
 
+/// Starts the action the player picks, unless it takes a perk they don't have.
+/// Waits while the player still has level up choices to make.
 pub fn handle_player_input(
     mut commands: Commands,
     mut keyboard_input_events: EventReader<KeyboardInput>,
     settings: Res<PlayerSettings>,
+    perk_tree: Res<PerkTree>,
     mut app_state: ResMut<State<AppState>>,
-    player_query: Query<Entity, (With<Player>, Without<Activity>)>,
+    player_query: Query<(Entity, Option<&Experience>), (With<Player>, Without<Activity>)>,
 ) {
     let mut success = false;
     keyboard_input_events.iter().for_each(|input| {
-        player_query.iter().for_each(|player_ent| {
-            if let Some(action) = try_into_action(input, &settings) {
+        player_query.iter().for_each(|(player_ent, experience)| {
+            if experience.map_or(false, |experience| experience.has_choices(&perk_tree)) {
+                return;
+            }
+            if let Some(action) = try_into_action(input, &settings)
+                .filter(|action| perk_tree.allows(*action, experience))
+            {
                 success = true;
 
                 commands.entity(player_ent).insert(Activity {
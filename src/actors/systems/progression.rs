@@ -0,0 +1,102 @@
+use bevy::{
+    input::{keyboard::KeyboardInput, ElementState},
+    prelude::*,
+};
+
+use crate::{
+    actors::{
+        constants::EXPERIENCE_PER_DIFFICULTY, Actor, Attribute, Experience, KillEvent,
+        ModifierSource, PerkTree, Player,
+    },
+    ai::MonsterDefinitions,
+    AppState,
+};
+
+/// Rewards killers with experience for the monsters they kill, more for harder ones.
+pub fn award_experience(
+    definitions: Res<MonsterDefinitions>,
+    mut kill_events: EventReader<KillEvent>,
+    mut killers: Query<&mut Experience>,
+) {
+    for kill in kill_events.iter() {
+        let killer = match kill.killer {
+            Some(killer) => killer,
+            None => continue,
+        };
+        let difficulty = definitions
+            .get(&kill.monster)
+            .map_or(0, |definition| definition.difficulty);
+        if let Ok(mut experience) = killers.get_mut(killer) {
+            experience.gain(difficulty * EXPERIENCE_PER_DIFFICULTY);
+        }
+    }
+}
+
+/// Brings up the level up screen on the player's turn while they have points to spend.
+pub fn open_level_up(
+    perk_tree: Res<PerkTree>,
+    mut app_state: ResMut<State<AppState>>,
+    player: Query<&Experience, With<Player>>,
+) {
+    if player
+        .iter()
+        .any(|experience| experience.has_choices(&perk_tree))
+    {
+        app_state.push(AppState::LevelUp).unwrap();
+    }
+}
+
+fn digit(input: &KeyboardInput) -> Option<usize> {
+    if input.state != ElementState::Pressed {
+        return None;
+    }
+    match input.key_code? {
+        KeyCode::Key1 => Some(1),
+        KeyCode::Key2 => Some(2),
+        KeyCode::Key3 => Some(3),
+        KeyCode::Key4 => Some(4),
+        KeyCode::Key5 => Some(5),
+        KeyCode::Key6 => Some(6),
+        KeyCode::Key7 => Some(7),
+        KeyCode::Key8 => Some(8),
+        KeyCode::Key9 => Some(9),
+        _ => None,
+    }
+}
+
+/// Spends the player's points as they pick by number: attributes first, then perks.
+/// Leaves the level up screen once there is nothing left to pick.
+pub fn handle_level_up_input(
+    perk_tree: Res<PerkTree>,
+    mut app_state: ResMut<State<AppState>>,
+    mut keyboard_input_events: EventReader<KeyboardInput>,
+    mut player: Query<(&mut Actor, &mut Experience), With<Player>>,
+) {
+    let (mut actor, mut experience) = match player.iter_mut().next() {
+        Some(player) => player,
+        None => return,
+    };
+
+    for choice in keyboard_input_events.iter().filter_map(digit) {
+        if experience.attribute_points > 0 {
+            if let Some(attribute) = Attribute::ALL.get(choice - 1) {
+                *actor.sheet.attributes.get_mut(*attribute) += 1;
+                experience.attribute_points -= 1;
+            }
+        } else if experience.perk_points > 0 {
+            let perk = match perk_tree.available(&experience).nth(choice - 1) {
+                Some(perk) => perk.clone(),
+                None => continue,
+            };
+            actor
+                .sheet
+                .set_modifiers(ModifierSource::Perk(perk.name.clone()), perk.modifiers);
+            experience.perks.push(perk.name);
+            experience.perk_points -= 1;
+        }
+    }
+
+    if !experience.has_choices(&perk_tree) {
+        app_state.pop().unwrap();
+    }
+}
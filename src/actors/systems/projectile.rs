@@ -0,0 +1,171 @@
+use bevy::prelude::*;
+
+use crate::{
+    actors::{constants::PROJECTILE_IMPACT_LOUDNESS, Projectile, StrikeEvent},
+    core::{
+        types::{GridPos, Increment, Index, Int, Real},
+        TimeIncrementEvent,
+    },
+    util::algorithms::line::{cover, tiles_crossed},
+    world::{NoiseEvent, WorldMap},
+};
+
+/// Moves projectiles along their path as time passes. A projectile stops at the first
+/// wall or actor it enters, striking the actor for less the more cover it has from
+/// where the projectile was launched. Actors leaving its path in time are not hit.
+pub fn advance_projectiles(
+    mut commands: Commands,
+    world_map: Res<WorldMap>,
+    mut time_events: EventReader<TimeIncrementEvent>,
+    mut projectiles: Query<(Entity, &mut GridPos, &mut Projectile)>,
+    mut strike_writer: EventWriter<StrikeEvent>,
+    mut noise_writer: EventWriter<NoiseEvent>,
+) {
+    let elapsed: Increment = time_events.iter().map(|event| event.delta_time).sum();
+    if elapsed == 0 {
+        return;
+    }
+
+    for (entity, mut pos, mut projectile) in projectiles.iter_mut() {
+        projectile.progress += projectile.velocity * elapsed as Real;
+        let steps = (projectile.progress as Index).min(projectile.path.len());
+        projectile.progress -= steps as Real;
+
+        let shooter = projectile.shooter;
+        let hits = |tile: &IVec2| {
+            world_map.is_blocking(tile) || world_map.actors_at(tile).any(|actor| actor != shooter)
+        };
+        let mut flight = vec![pos.0];
+        flight.extend(projectile.path.drain(..steps));
+        let reached = match tiles_crossed(&flight, &hits).last() {
+            Some(reached) => *reached,
+            None => continue,
+        };
+
+        if world_map.is_blocking(&reached) {
+            noise_writer.send(NoiseEvent {
+                origin: reached,
+                loudness: PROJECTILE_IMPACT_LOUDNESS,
+            });
+        } else if hits(&reached) {
+            let exposure = 1.0
+                - cover(&projectile.origin, &reached, &|tile| {
+                    world_map.is_blocking(tile)
+                });
+            strike_writer.send(StrikeEvent {
+                attacker: shooter,
+                positions: vec![reached],
+                damage: (projectile.damage as Real * exposure).round() as Int,
+                knockback: None,
+            });
+        } else {
+            pos.0 = reached;
+            if !projectile.path.is_empty() {
+                continue;
+            }
+        }
+        commands.entity(entity).despawn();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::prelude::*;
+
+    use crate::{
+        actors::{ActorBundle, Projectile, StrikeEvent},
+        core::{types::GridPos, TimeIncrementEvent},
+        test::helpers::world::{pass_time, sent_events, world_from_ascii_layout},
+        world::{systems::update_spatial_index, NoiseEvent},
+    };
+
+    use super::advance_projectiles;
+
+    /// A shooter at `@` firing east, a target standing at `target` and the projectile.
+    fn range(layout: &str, target: IVec2) -> (World, [Entity; 3]) {
+        let (mut world, start) = world_from_ascii_layout(layout);
+        world.insert_resource(Events::<TimeIncrementEvent>::default());
+        world.insert_resource(Events::<StrikeEvent>::default());
+        world.insert_resource(Events::<NoiseEvent>::default());
+        let shooter = world
+            .spawn()
+            .insert_bundle(ActorBundle {
+                position: GridPos(start),
+                ..Default::default()
+            })
+            .id();
+        let target = world
+            .spawn()
+            .insert_bundle(ActorBundle {
+                position: GridPos(target),
+                ..Default::default()
+            })
+            .id();
+        let projectile = world
+            .spawn()
+            .insert(GridPos(start))
+            .insert(Projectile::aimed(
+                shooter,
+                start,
+                start + IVec2::X,
+                10,
+                0.1,
+                15,
+            ))
+            .id();
+        SystemStage::single(update_spatial_index.system()).run(&mut world);
+        (world, [shooter, target, projectile])
+    }
+
+    #[test]
+    fn flies_at_its_own_pace_until_it_hits() {
+        let (mut world, [shooter, _, projectile]) = range(
+            "#########
+             #@......#
+             #.......#
+             #########",
+            IVec2::new(5, 1),
+        );
+        let mut stage = SystemStage::single(advance_projectiles.system());
+
+        pass_time(&mut world, &mut stage, 25);
+        assert_eq!(
+            IVec2::new(3, 1),
+            world.get::<GridPos>(projectile).unwrap().0
+        );
+        assert!(sent_events::<StrikeEvent>(&world).is_empty());
+
+        pass_time(&mut world, &mut stage, 30);
+        assert!(world.get_entity(projectile).is_none());
+        let strikes = sent_events::<StrikeEvent>(&world);
+        assert_eq!(1, strikes.len());
+        assert_eq!(shooter, strikes[0].attacker);
+        assert_eq!(vec![IVec2::new(5, 1)], strikes[0].positions);
+        assert_eq!(15, strikes[0].damage);
+    }
+
+    #[test]
+    fn actors_can_step_out_of_the_way() {
+        let (mut world, [_, target, projectile]) = range(
+            "#########
+             #@......#
+             #.......#
+             #########",
+            IVec2::new(5, 1),
+        );
+        let mut stage = SystemStage::single(advance_projectiles.system());
+        pass_time(&mut world, &mut stage, 25);
+
+        world.get_mut::<GridPos>(target).unwrap().0 = IVec2::new(5, 2);
+        SystemStage::single(update_spatial_index.system()).run(&mut world);
+
+        pass_time(&mut world, &mut stage, 100);
+        assert!(world.get_entity(projectile).is_none());
+        assert!(sent_events::<StrikeEvent>(&world).is_empty());
+        let impacts: Vec<IVec2> = sent_events::<NoiseEvent>(&world)
+            .iter()
+            .map(|noise| noise.origin)
+            .collect();
+        assert_eq!(vec![IVec2::new(8, 1)], impacts);
+    }
+}
@@ -0,0 +1,110 @@
+use bevy::prelude::*;
+
+use crate::{
+    actors::{compute_next_position, Action, Activity, Actor, PushEvent, Weapon},
+    core::types::{Direction, GridPos},
+    world::{Viewshed, WorldMap},
+};
+
+/// Moves pushed actors a tile along the push when nothing stands in the way,
+/// staggering them. A push into a wall or another actor does nothing.
+pub fn resolve_pushes(
+    mut commands: Commands,
+    world_map: Res<WorldMap>,
+    mut push_events: EventReader<PushEvent>,
+    mut targets: Query<(&Children, &Actor, &mut GridPos, &mut Viewshed), Without<Weapon>>,
+    mut weapons: Query<&mut GridPos, With<Weapon>>,
+) {
+    for push in push_events.iter() {
+        let (children, actor, mut pos, mut viewshed) = match targets.get_mut(push.target) {
+            Ok(target) => target,
+            Err(_) => continue,
+        };
+        let destination = compute_next_position(Direction::Forward, push.direction, &pos.0);
+        if world_map.is_blocking(&destination)
+            || world_map.blocking_entity_at(&destination).is_some()
+        {
+            continue;
+        }
+
+        pos.0 = destination;
+        for weapon in children.iter() {
+            if let Ok(mut weapon_pos) = weapons.get_mut(*weapon) {
+                weapon_pos.0 = compute_next_position(Direction::Forward, actor.facing, &pos.0);
+            }
+        }
+        viewshed.dirty = true;
+
+        if push.stagger > 0 {
+            commands.entity(push.target).insert(Activity {
+                action: Action::Wait,
+                time_to_complete: push.stagger,
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::prelude::*;
+
+    use crate::{
+        actors::{Action, Activity, ActorBundle, PushEvent, WeaponBundle},
+        core::types::{Cardinal, GridPos},
+        test::helpers::world::world_from_ascii_layout,
+        world::systems::update_spatial_index,
+    };
+
+    use super::resolve_pushes;
+
+    fn push_world(layout: &str) -> (World, Entity) {
+        let (mut world, start) = world_from_ascii_layout(layout);
+        let target = world
+            .spawn()
+            .insert_bundle(ActorBundle {
+                position: GridPos(start),
+                ..Default::default()
+            })
+            .with_children(|actor| {
+                actor.spawn().insert_bundle(WeaponBundle::default());
+            })
+            .id();
+        let mut events = Events::<PushEvent>::default();
+        events.send(PushEvent {
+            target,
+            direction: Cardinal::East,
+            stagger: 45,
+        });
+        world.insert_resource(events);
+        SystemStage::single(update_spatial_index.system()).run(&mut world);
+        (world, target)
+    }
+
+    #[test]
+    fn pushed_actor_moves_and_staggers() {
+        let (mut world, target) = push_world(
+            "#####
+             #@..#
+             #####",
+        );
+        SystemStage::single(resolve_pushes.system()).run(&mut world);
+
+        assert_eq!(IVec2::new(2, 1), world.get::<GridPos>(target).unwrap().0);
+        let activity = world.get::<Activity>(target).unwrap();
+        assert_eq!(Action::Wait, activity.action);
+        assert_eq!(45, activity.time_to_complete);
+    }
+
+    #[test]
+    fn walls_stop_a_push() {
+        let (mut world, target) = push_world(
+            "####
+             #.@#
+             ####",
+        );
+        SystemStage::single(resolve_pushes.system()).run(&mut world);
+
+        assert_eq!(IVec2::new(2, 1), world.get::<GridPos>(target).unwrap().0);
+        assert!(world.get::<Activity>(target).is_none());
+    }
+}
@@ -0,0 +1,294 @@
+use bevy::prelude::*;
+
+use crate::{
+    actors::{
+        constants::{BASE_ATTRIBUTE, BASE_SPEED, BLEED_DAMAGE, STATUS_TICK},
+        effects::Telegraph,
+        Action, Activity, Actor, DeathEvent, ModifierSource, Stat, StatusEvent, StatusKind,
+        Statuses,
+    },
+    core::{
+        types::{GridPos, Increment, Int},
+        TimeIncrementEvent,
+    },
+    world::Viewshed,
+};
+
+/// Puts statuses on their targets, for less time the more resolve they have. A stun
+/// cuts short whatever the target was doing, telegraphed attacks included, and keeps
+/// it waiting until the stun wears off.
+pub fn apply_statuses(
+    mut commands: Commands,
+    mut status_events: EventReader<StatusEvent>,
+    mut targets: Query<(&mut Actor, &mut Statuses, &mut Viewshed)>,
+    telegraphs: Query<(Entity, &Telegraph)>,
+) {
+    for event in status_events.iter() {
+        let (mut actor, mut statuses, mut viewshed) = match targets.get_mut(event.target) {
+            Ok(target) => target,
+            Err(_) => continue,
+        };
+        let duration = event.duration * BASE_ATTRIBUTE as Increment
+            / actor.sheet.stat(Stat::Resolve) as Increment;
+        if let Some(opposite) = event.kind.opposite() {
+            actor
+                .sheet
+                .remove_modifiers(&ModifierSource::Status(opposite));
+        }
+        if statuses.apply(event.kind, duration) {
+            actor
+                .sheet
+                .set_modifiers(ModifierSource::Status(event.kind), event.kind.adjustments());
+            if let Some(modifier) = event.kind.view_modifier() {
+                viewshed.add_modifier(modifier);
+            }
+        }
+
+        if event.kind == StatusKind::Stunned {
+            commands.entity(event.target).insert(Activity {
+                action: Action::Wait,
+                time_to_complete: statuses.time_left(StatusKind::Stunned),
+            });
+            for (telegraph, _) in telegraphs
+                .iter()
+                .filter(|(_, telegraph)| telegraph.attacker == event.target)
+            {
+                commands.entity(telegraph).despawn();
+            }
+        }
+    }
+}
+
+/// Counts statuses down as time passes, dealing bleeding and poison damage every
+/// status tick and lifting statuses once they run out.
+pub fn progress_statuses(
+    mut time_events: EventReader<TimeIncrementEvent>,
+    mut death_writer: EventWriter<DeathEvent>,
+    mut actors: Query<(Entity, &mut Actor, &mut Statuses, &mut Viewshed)>,
+) {
+    let elapsed: Increment = time_events.iter().map(|event| event.delta_time).sum();
+    if elapsed == 0 {
+        return;
+    }
+
+    for (entity, mut actor, mut statuses, mut viewshed) in actors.iter_mut() {
+        if statuses.active.is_empty() {
+            continue;
+        }
+
+        let mut damage = 0;
+        for status in statuses.active.iter_mut() {
+            let elapsed = elapsed.min(status.time_left);
+            status.since_tick += elapsed;
+            let ticks = status.since_tick / STATUS_TICK;
+            status.since_tick %= STATUS_TICK;
+            damage += status.kind.damage_per_tick() * status.stacks * ticks as Int;
+            status.time_left -= elapsed;
+        }
+        for status in statuses
+            .active
+            .iter()
+            .filter(|status| status.time_left == 0)
+        {
+            actor
+                .sheet
+                .remove_modifiers(&ModifierSource::Status(status.kind));
+            if let Some(modifier) = status.kind.view_modifier() {
+                viewshed.remove_modifier(modifier);
+            }
+        }
+        statuses.active.retain(|status| status.time_left > 0);
+
+        if damage > 0 && !actor.is_dead() {
+            actor.take_damage(damage);
+            if actor.is_dead() {
+                death_writer.send(DeathEvent {
+                    entity,
+                    killer: None,
+                });
+            }
+        }
+    }
+}
+
+/// Shortens newly started activities of actors faster than ordinary and stretches
+/// those of slower ones. The wait of a stunned actor lasts exactly as long as the stun.
+pub fn scale_activity_time(mut actors: Query<(&mut Activity, &Actor, &Statuses), Added<Activity>>) {
+    for (mut activity, actor, statuses) in actors.iter_mut() {
+        if !statuses.has(StatusKind::Stunned) {
+            activity.time_to_complete = activity.time_to_complete * BASE_SPEED as Increment
+                / actor.sheet.stat(Stat::Speed) as Increment;
+        }
+    }
+}
+
+/// Widens or narrows actors' view along with their sight range.
+pub fn update_sight_range(mut actors: Query<(&Actor, &mut Viewshed), Changed<Actor>>) {
+    for (actor, mut viewshed) in actors.iter_mut() {
+        let range_bonus = actor.sheet.stat(Stat::SightRange);
+        if viewshed.range_bonus != range_bonus {
+            viewshed.range_bonus = range_bonus;
+            viewshed.dirty = true;
+        }
+    }
+}
+
+/// Reopens the wounds of bleeding actors whenever they move.
+pub fn bleed_on_move(
+    mut death_writer: EventWriter<DeathEvent>,
+    mut actors: Query<(Entity, &mut Actor, &Statuses), Changed<GridPos>>,
+) {
+    for (entity, mut actor, statuses) in actors.iter_mut() {
+        let stacks = statuses.stacks(StatusKind::Bleeding);
+        if stacks == 0 || actor.is_dead() {
+            continue;
+        }
+        actor.take_damage(BLEED_DAMAGE * stacks);
+        if actor.is_dead() {
+            death_writer.send(DeathEvent {
+                entity,
+                killer: None,
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::prelude::*;
+
+    use crate::{
+        actors::{
+            effects::Telegraph, Action, Activity, Actor, ActorBundle, DeathEvent, StatusEvent,
+            StatusKind,
+        },
+        core::TimeIncrementEvent,
+        test::helpers::world::{pass_time, sent_events},
+        world::{ViewModifier, Viewshed},
+    };
+
+    use super::{apply_statuses, progress_statuses, scale_activity_time};
+
+    fn afflict(world: &mut World, stage: &mut SystemStage, event: StatusEvent) {
+        world
+            .get_resource_mut::<Events<StatusEvent>>()
+            .unwrap()
+            .send(event);
+        stage.run(world);
+    }
+
+    fn status_world() -> (World, Entity) {
+        let mut world = World::new();
+        world.insert_resource(Events::<StatusEvent>::default());
+        world.insert_resource(Events::<TimeIncrementEvent>::default());
+        world.insert_resource(Events::<DeathEvent>::default());
+        let target = world
+            .spawn()
+            .insert(Activity {
+                action: Action::Attack,
+                time_to_complete: 40,
+            })
+            .insert_bundle(ActorBundle::default())
+            .id();
+        (world, target)
+    }
+
+    #[test]
+    fn stun_interrupts_what_the_target_was_doing() {
+        let (mut world, target) = status_world();
+        let telegraph = world.spawn().insert(Telegraph { attacker: target }).id();
+        let mut stage = SystemStage::single(apply_statuses.system());
+
+        afflict(
+            &mut world,
+            &mut stage,
+            StatusEvent {
+                target,
+                kind: StatusKind::Stunned,
+                duration: 60,
+            },
+        );
+        let activity = world.get::<Activity>(target).unwrap();
+        assert_eq!(Action::Wait, activity.action);
+        assert_eq!(60, activity.time_to_complete);
+        assert!(world.get_entity(telegraph).is_none());
+        assert!(world
+            .get::<Viewshed>(target)
+            .unwrap()
+            .modifiers
+            .contains(&ViewModifier::TunnelVision));
+
+        let mut stage = SystemStage::single(progress_statuses.system());
+        pass_time(&mut world, &mut stage, 60);
+        assert!(world.get::<Viewshed>(target).unwrap().modifiers.is_empty());
+    }
+
+    #[test]
+    fn bleeding_ticks_harder_with_every_stack() {
+        let (mut world, target) = status_world();
+        let mut apply = SystemStage::single(apply_statuses.system());
+        for _ in 0..2 {
+            afflict(
+                &mut world,
+                &mut apply,
+                StatusEvent {
+                    target,
+                    kind: StatusKind::Bleeding,
+                    duration: 90,
+                },
+            );
+        }
+        let health = |world: &World| world.get::<Actor>(target).unwrap().properties.health;
+
+        let mut stage = SystemStage::single(progress_statuses.system());
+        pass_time(&mut world, &mut stage, 20);
+        assert_eq!(100.0, health(&world).value());
+
+        pass_time(&mut world, &mut stage, 10);
+        assert_eq!(96.0, health(&world).value());
+
+        pass_time(&mut world, &mut stage, 300);
+        assert_eq!(88.0, health(&world).value());
+    }
+
+    #[test]
+    fn bleeding_out_is_nobody_s_kill() {
+        let (mut world, target) = status_world();
+        world.get_mut::<Actor>(target).unwrap().take_damage(98);
+        afflict(
+            &mut world,
+            &mut SystemStage::single(apply_statuses.system()),
+            StatusEvent {
+                target,
+                kind: StatusKind::Bleeding,
+                duration: 90,
+            },
+        );
+
+        let mut stage = SystemStage::single(progress_statuses.system());
+        pass_time(&mut world, &mut stage, 30);
+        let deaths = sent_events::<DeathEvent>(&world);
+        assert_eq!(1, deaths.len());
+        assert_eq!(target, deaths[0].entity);
+        assert_eq!(None, deaths[0].killer);
+
+        pass_time(&mut world, &mut stage, 30);
+        assert_eq!(1, sent_events::<DeathEvent>(&world).len());
+    }
+
+    #[test]
+    fn slowed_actors_take_longer() {
+        let (mut world, target) = status_world();
+        afflict(
+            &mut world,
+            &mut SystemStage::single(apply_statuses.system()),
+            StatusEvent {
+                target,
+                kind: StatusKind::Slowed,
+                duration: 90,
+            },
+        );
+        SystemStage::single(scale_activity_time.system()).run(&mut world);
+        assert_eq!(60, world.get::<Activity>(target).unwrap().time_to_complete);
+    }
+}
@@ -3,10 +3,15 @@ use bevy::{
     prelude::{Bundle, Color, Component},
 };
 
-use crate::{core::types::GridPos, world::Renderable};
+use crate::{actors::Adjustment, core::types::GridPos, world::Renderable};
 
 #[derive(Debug, Component, Default)]
-pub struct Weapon;
+pub struct Weapon {
+    /// Heavy weapons knock whoever they hit back a tile.
+    pub knockback: bool,
+    /// Adjustments to the stats of whoever wields the weapon.
+    pub modifiers: Vec<Adjustment>,
+}
 
 #[derive(Debug, Bundle)]
 pub struct WeaponBundle {
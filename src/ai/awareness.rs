@@ -0,0 +1,102 @@
+use bevy::{
+    math::IVec2,
+    prelude::{Component, Entity},
+};
+
+use crate::{
+    ai::constants::{ALERTED_AWARENESS, SUSPICIOUS_AWARENESS},
+    core::types::Real,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AwarenessState {
+    Unaware,
+    /// Has noticed something and looks into it.
+    Suspicious,
+    /// Knows where the player is and treats them as a target.
+    Alerted,
+}
+
+/// How sure a monster is that the player is around, built up over time.
+#[derive(Debug, Clone, Copy, Default, Component)]
+pub struct Awareness {
+    pub level: Real,
+    /// Where the player was last seen or heard.
+    pub last_sensed: Option<IVec2>,
+    /// The player this awareness is about, once they have been sensed.
+    pub subject: Option<Entity>,
+}
+
+impl Awareness {
+    pub fn state(&self) -> AwarenessState {
+        if self.level >= ALERTED_AWARENESS {
+            AwarenessState::Alerted
+        } else if self.level >= SUSPICIOUS_AWARENESS {
+            AwarenessState::Suspicious
+        } else {
+            AwarenessState::Unaware
+        }
+    }
+
+    pub fn raise(&mut self, amount: Real, subject: Entity, at: IVec2) {
+        self.level = (self.level + amount).min(ALERTED_AWARENESS);
+        self.subject = Some(subject);
+        self.last_sensed = Some(at);
+    }
+
+    /// Lowers awareness, forgetting where the player was once no longer suspicious.
+    pub fn decay(&mut self, amount: Real) {
+        self.level = (self.level - amount).max(0.0);
+        if self.state() == AwarenessState::Unaware {
+            self.last_sensed = None;
+        }
+    }
+
+    pub fn alert(&mut self, subject: Entity, at: IVec2) {
+        self.raise(ALERTED_AWARENESS, subject, at);
+    }
+
+    /// Whether `entity` can be targeted. Only the player has to be noticed first.
+    pub fn notices(&self, entity: Entity) -> bool {
+        self.subject != Some(entity) || self.state() == AwarenessState::Alerted
+    }
+
+    /// Spot worth investigating while suspicious.
+    pub fn suspected_position(&self) -> Option<IVec2> {
+        match self.state() {
+            AwarenessState::Suspicious => self.last_sensed,
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::{math::IVec2, prelude::Entity};
+
+    use super::{Awareness, AwarenessState};
+
+    #[test]
+    fn builds_up_and_fades() {
+        let player = Entity::from_raw(0);
+        let spot = IVec2::new(3, 4);
+        let mut awareness = Awareness::default();
+        assert_eq!(AwarenessState::Unaware, awareness.state());
+
+        awareness.raise(0.5, player, spot);
+        assert_eq!(AwarenessState::Suspicious, awareness.state());
+        assert_eq!(Some(spot), awareness.suspected_position());
+        assert!(!awareness.notices(player));
+
+        awareness.raise(0.7, player, spot);
+        assert_eq!(AwarenessState::Alerted, awareness.state());
+        assert!(awareness.notices(player));
+        assert_eq!(None, awareness.suspected_position());
+
+        awareness.decay(0.9);
+        assert_eq!(AwarenessState::Unaware, awareness.state());
+        assert_eq!(None, awareness.last_sensed);
+        assert!(!awareness.notices(player));
+        assert!(awareness.notices(Entity::from_raw(1)));
+    }
+}
@@ -0,0 +1,23 @@
+use crate::core::types::{Int, Real};
+
+/// Distance from which a monster facing its target starts an attack.
+pub const ATTACK_REACH: Int = 3;
+/// Farthest path length a monster looks for when making its way around obstacles.
+pub const NAVIGATION_RANGE: Int = 40;
+
+/// Awareness at which a monster turns suspicious and starts looking around.
+pub const SUSPICIOUS_AWARENESS: Real = 0.3;
+/// Awareness at which a monster is sure of the player and attacks.
+pub const ALERTED_AWARENESS: Real = 1.0;
+/// Awareness gained per increment while the player stands fully exposed.
+pub const SIGHT_AWARENESS_RATE: Real = 1.0 / 60.0;
+/// Awareness lost per increment without any sign of the player.
+pub const AWARENESS_DECAY_RATE: Real = 1.0 / 900.0;
+/// Awareness gained per point of loudness of a noise made by the player.
+pub const NOISE_AWARENESS: Real = 0.05;
+/// How much of the player's exposure counts at the very edge of the view.
+pub const PERIPHERAL_FOCUS: Real = 0.25;
+/// Light level darkvision makes a nearby player appear to stand in.
+pub const DARKVISION_EXPOSURE: Real = 0.5;
+/// Farthest distance that still counts in how close the player is.
+pub const NOTICE_DISTANCE: Int = 15;
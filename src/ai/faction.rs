@@ -0,0 +1,109 @@
+use std::collections::HashMap;
+
+use bevy::prelude::Component;
+use serde::Deserialize;
+
+use crate::util::helpers::deserialize;
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Deserialize, Component)]
+pub struct Faction(pub String);
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Deserialize)]
+pub enum Relation {
+    Hostile,
+    Neutral,
+    Allied,
+}
+
+#[derive(Debug, Deserialize)]
+struct RelationEntry {
+    factions: (String, String),
+    relation: Relation,
+}
+
+#[derive(Debug, Deserialize)]
+struct FactionTable {
+    default: Relation,
+    relations: Vec<RelationEntry>,
+}
+
+/// Symmetric relations between factions. Members of the same faction are always allied.
+#[derive(Debug)]
+pub struct FactionRelations {
+    default: Relation,
+    relations: HashMap<(String, String), Relation>,
+}
+
+impl Default for FactionRelations {
+    fn default() -> Self {
+        let table: FactionTable = deserialize("assets/data/factions.ron");
+        table.into()
+    }
+}
+
+impl From<FactionTable> for FactionRelations {
+    fn from(table: FactionTable) -> Self {
+        let mut relations = HashMap::new();
+        for entry in table.relations {
+            let (first, second) = entry.factions;
+            relations.insert((second.clone(), first.clone()), entry.relation);
+            relations.insert((first, second), entry.relation);
+        }
+        Self {
+            default: table.default,
+            relations,
+        }
+    }
+}
+
+impl FactionRelations {
+    pub fn relation(&self, first: &Faction, second: &Faction) -> Relation {
+        if first == second {
+            return Relation::Allied;
+        }
+        self.relations
+            .get(&(first.0.clone(), second.0.clone()))
+            .copied()
+            .unwrap_or(self.default)
+    }
+
+    pub fn is_hostile(&self, first: &Faction, second: &Faction) -> bool {
+        self.relation(first, second) == Relation::Hostile
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Faction, FactionRelations, FactionTable, Relation, RelationEntry};
+
+    fn relations() -> FactionRelations {
+        FactionTable {
+            default: Relation::Neutral,
+            relations: vec![RelationEntry {
+                factions: ("player".to_string(), "goblins".to_string()),
+                relation: Relation::Hostile,
+            }],
+        }
+        .into()
+    }
+
+    #[test]
+    fn relations_are_symmetric() {
+        let relations = relations();
+        let player = Faction("player".to_string());
+        let goblins = Faction("goblins".to_string());
+
+        assert!(relations.is_hostile(&player, &goblins));
+        assert!(relations.is_hostile(&goblins, &player));
+    }
+
+    #[test]
+    fn unlisted_relations_use_default() {
+        let relations = relations();
+        let goblins = Faction("goblins".to_string());
+        let rats = Faction("rats".to_string());
+
+        assert_eq!(Relation::Neutral, relations.relation(&goblins, &rats));
+        assert_eq!(Relation::Allied, relations.relation(&rats, &rats));
+    }
+}
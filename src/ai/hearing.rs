@@ -0,0 +1,35 @@
+use bevy::{math::IVec2, prelude::Component};
+
+use crate::core::types::Int;
+
+#[derive(Debug, Clone, Copy)]
+pub struct HeardNoise {
+    pub origin: IVec2,
+    pub loudness: Int,
+}
+
+#[derive(Debug, Default, Component)]
+pub struct Hearing {
+    pub threshold: Int,
+    pub heard: Option<HeardNoise>,
+}
+
+impl Hearing {
+    pub fn with_threshold(threshold: Int) -> Self {
+        Self {
+            threshold,
+            heard: None,
+        }
+    }
+
+    /// Remembers the noise if it is audible and louder than the one currently being investigated.
+    pub fn hear(&mut self, noise: HeardNoise) {
+        if noise.loudness < self.threshold {
+            return;
+        }
+        match self.heard {
+            Some(heard) if heard.loudness > noise.loudness => (),
+            _ => self.heard = Some(noise),
+        }
+    }
+}
@@ -1,23 +1,70 @@
 use bevy::prelude::*;
 
-use self::systems::monster_ai;
+use self::systems::{
+    assign_squad_slots, disband_empty_squads, hear_noises, monster_ai, share_squad_knowledge,
+    squad_ai, update_awareness, utility_ai,
+};
 
 pub mod systems;
 
+pub mod constants;
+
+mod awareness;
+pub use awareness::{Awareness, AwarenessState};
+
+mod faction;
+pub use faction::{Faction, FactionRelations, Relation};
+
+mod hearing;
+pub use hearing::{HeardNoise, Hearing};
+
 mod monster;
-pub use monster::Monster;
+pub use monster::{Monster, MonsterDefinition, MonsterDefinitions};
+
+pub mod navigation;
+
+pub mod squad;
+pub use squad::{Squad, SquadMember};
+
+pub mod targeting;
+
+pub mod utility;
+pub use utility::{UtilityAI, UtilityWeights};
 
 pub struct AIPlugin;
 
 impl Plugin for AIPlugin {
     fn build(&self, app: &mut App) {
-        app.add_system_set(
-            SystemSet::new()
-                .label(AISystems)
-                .with_system(monster_ai.system()),
-        );
+        app.init_resource::<FactionRelations>()
+            .init_resource::<MonsterDefinitions>()
+            .add_system_set(
+                SystemSet::new()
+                    .label(AISystems::Perception)
+                    .with_system(update_awareness.system())
+                    .with_system(hear_noises.system())
+                    .with_system(share_squad_knowledge.system()),
+            )
+            .add_system_set(
+                SystemSet::new()
+                    .label(AISystems::Coordination)
+                    .after(AISystems::Perception)
+                    .with_system(assign_squad_slots.system())
+                    .with_system(disband_empty_squads.system()),
+            )
+            .add_system_set(
+                SystemSet::new()
+                    .label(AISystems::Decision)
+                    .after(AISystems::Coordination)
+                    .with_system(monster_ai.system())
+                    .with_system(utility_ai.system())
+                    .with_system(squad_ai.system()),
+            );
     }
 }
 
 #[derive(Clone, Hash, Debug, PartialEq, Eq, SystemLabel)]
-struct AISystems;
+enum AISystems {
+    Perception,
+    Coordination,
+    Decision,
+}
@@ -1,4 +1,71 @@
-use bevy::prelude::Component;
+use bevy::prelude::{Color, Component};
+use serde::Deserialize;
+
+use crate::{
+    actors::Inflicts,
+    ai::UtilityWeights,
+    core::types::{Int, Real},
+    util::{algorithms::field_of_view::FOV, helpers::deserialize},
+};
 
 #[derive(Debug, Component)]
-pub struct Monster {}
+pub struct Monster {
+    pub kind: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct MonsterDefinition {
+    pub kind: String,
+    pub glyph: char,
+    pub color: (Real, Real, Real),
+    pub faction: String,
+    pub fov: FOV,
+    pub hearing_threshold: Int,
+    /// Monsters spawned together share a squad when there is more than one.
+    #[serde(default)]
+    pub pack_size: Int,
+    /// Monsters with weights decide through utility scoring rather than fixed rules.
+    #[serde(default)]
+    pub utility: Option<UtilityWeights>,
+    /// Shallowest level the monster is generated on.
+    #[serde(default)]
+    pub min_depth: Int,
+    #[serde(default)]
+    pub opens_doors: bool,
+    /// Hits hard enough to knock targets back a tile.
+    #[serde(default)]
+    pub knockback: bool,
+    /// Tiles away the monster sees without light.
+    #[serde(default)]
+    pub darkvision: Int,
+    /// How hard the monster is to kill, scaling the experience it is worth.
+    #[serde(default)]
+    pub difficulty: Int,
+    /// Status the monster's hits put on whoever survives them.
+    #[serde(default)]
+    pub inflicts: Option<Inflicts>,
+}
+
+impl MonsterDefinition {
+    pub fn fg(&self) -> Color {
+        let (r, g, b) = self.color;
+        Color::rgb(r, g, b)
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct MonsterDefinitions {
+    pub monsters: Vec<MonsterDefinition>,
+}
+
+impl Default for MonsterDefinitions {
+    fn default() -> Self {
+        deserialize("assets/data/monsters.ron")
+    }
+}
+
+impl MonsterDefinitions {
+    pub fn get(&self, kind: &str) -> Option<&MonsterDefinition> {
+        self.monsters.iter().find(|monster| monster.kind == kind)
+    }
+}
@@ -0,0 +1,128 @@
+use bevy::math::IVec2;
+
+use crate::{
+    actors::Action,
+    ai::constants::NAVIGATION_RANGE,
+    core::types::{Cardinal, Direction, Int},
+    util::algorithms::{
+        geometry::{cardinal_towards, chessboard_neighbours},
+        pathfinding::dijkstra_map,
+    },
+    world::WorldMap,
+};
+
+/// Direction to use relative to `facing` in order to head towards `cardinal`.
+pub fn relative_direction(facing: Cardinal, cardinal: Cardinal) -> Direction {
+    let offset = Int::from(cardinal) - Int::from(facing);
+    offset.rem_euclid(8).into()
+}
+
+/// Turns towards the target first, then walks straight at it.
+pub fn step_towards(pos: &IVec2, facing: Cardinal, target: &IVec2) -> Option<Action> {
+    let cardinal = cardinal_towards(pos, target)?;
+    if cardinal == facing {
+        Some(Action::Move(Direction::Forward))
+    } else {
+        Some(Action::Turn(relative_direction(facing, cardinal)))
+    }
+}
+
+/// Whether a walker can't step onto `pos`. Closed doors only stop walkers that
+/// can't open them.
+pub fn blocks_walker(world_map: &WorldMap, pos: &IVec2, opens_doors: bool) -> bool {
+    world_map.is_blocking(pos) && !(opens_doors && world_map.is_closed_door(pos))
+}
+
+/// Like `step_towards`, but heads along the shortest path around blocked positions.
+/// `None` once at the target, or when no path within `NAVIGATION_RANGE` reaches it.
+pub fn path_towards(
+    pos: &IVec2,
+    facing: Cardinal,
+    target: &IVec2,
+    is_blocked: &dyn Fn(&IVec2) -> bool,
+) -> Option<Action> {
+    if pos == target {
+        return None;
+    }
+    let costs = dijkstra_map(&[*target], NAVIGATION_RANGE, &|step| {
+        if is_blocked(step) {
+            None
+        } else {
+            Some(1)
+        }
+    });
+    let next = chessboard_neighbours(pos)
+        .filter_map(|neighbour| costs.get(&neighbour).map(|cost| (*cost, neighbour)))
+        .min_by_key(|(cost, neighbour)| {
+            let offset = (*neighbour - *target).abs();
+            (*cost, offset.x + offset.y)
+        })?
+        .1;
+    step_towards(pos, facing, &next)
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::math::IVec2;
+
+    use crate::{
+        actors::Action,
+        core::types::{Cardinal, Direction},
+    };
+
+    use super::{path_towards, relative_direction, step_towards};
+
+    #[test]
+    fn relative_directions() {
+        assert_eq!(
+            Direction::Forward,
+            relative_direction(Cardinal::West, Cardinal::West)
+        );
+        assert_eq!(
+            Direction::Right,
+            relative_direction(Cardinal::North, Cardinal::East)
+        );
+        assert_eq!(
+            Direction::ForwardLeft,
+            relative_direction(Cardinal::North, Cardinal::NorthWest)
+        );
+        assert_eq!(
+            Direction::Back,
+            relative_direction(Cardinal::SouthEast, Cardinal::NorthWest)
+        );
+    }
+
+    #[test]
+    fn turn_then_walk() {
+        let target = IVec2::new(5, 0);
+        assert_eq!(
+            Some(Action::Turn(Direction::Right)),
+            step_towards(&IVec2::ZERO, Cardinal::North, &target)
+        );
+        assert_eq!(
+            Some(Action::Move(Direction::Forward)),
+            step_towards(&IVec2::ZERO, Cardinal::East, &target)
+        );
+        assert_eq!(None, step_towards(&target, Cardinal::East, &target));
+    }
+
+    #[test]
+    fn paths_around_walls() {
+        let wall = |pos: &IVec2| pos.x == 1 && pos.y >= -1;
+        let target = IVec2::new(3, 0);
+        assert_eq!(
+            Some(Action::Turn(Direction::Left)),
+            path_towards(&IVec2::ZERO, Cardinal::East, &target, &wall)
+        );
+        assert_eq!(
+            Some(Action::Move(Direction::Forward)),
+            path_towards(&IVec2::ZERO, Cardinal::North, &target, &wall)
+        );
+
+        let sealed = |pos: &IVec2| pos.x == 1;
+        assert_eq!(
+            None,
+            path_towards(&IVec2::ZERO, Cardinal::East, &target, &sealed)
+        );
+    }
+}
@@ -0,0 +1,139 @@
+use bevy::{math::IVec2, prelude::*};
+
+use crate::{
+    actors::compute_next_position,
+    core::types::{Cardinal, Direction, Increment, Int},
+    util::algorithms::geometry::chessboard_distance,
+};
+
+/// Minimum game time between two squad members starting an attack.
+pub const WINDUP_STAGGER: Increment = 30;
+
+/// Distance from the target at which members take their positions.
+const SLOT_DISTANCE: Int = 2;
+
+/// Knowledge shared by every member of a group.
+#[derive(Debug, Default, Component)]
+pub struct Squad {
+    pub target: Option<Entity>,
+    pub last_known_position: Option<IVec2>,
+    pub last_known_facing: Option<Cardinal>,
+    pub last_windup: Option<Increment>,
+}
+
+impl Squad {
+    pub fn can_wind_up(&self, now: Increment) -> bool {
+        self.last_windup
+            .map_or(true, |last_windup| now >= last_windup + WINDUP_STAGGER)
+    }
+}
+
+#[derive(Debug, Clone, Copy, Component)]
+pub struct SquadMember {
+    pub squad: Entity,
+    pub slot: Option<IVec2>,
+}
+
+impl SquadMember {
+    pub fn of(squad: Entity) -> Self {
+        Self { squad, slot: None }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SquadRole {
+    Front,
+    Behind,
+    LeftFlank,
+    RightFlank,
+}
+
+/// Positions around a target relative to where it faces, by order of priority.
+pub fn formation_slots(target: &IVec2, facing: Cardinal) -> Vec<(SquadRole, IVec2)> {
+    let slot = |direction: Direction| {
+        let mut pos = *target;
+        for _ in 0..SLOT_DISTANCE {
+            pos = compute_next_position(direction, facing, &pos);
+        }
+        pos
+    };
+
+    vec![
+        (SquadRole::Front, slot(Direction::Forward)),
+        (SquadRole::Behind, slot(Direction::Back)),
+        (SquadRole::LeftFlank, slot(Direction::Left)),
+        (SquadRole::RightFlank, slot(Direction::Right)),
+    ]
+}
+
+/// Greedily hands each slot, in priority order, to the closest member still without one.
+/// Members beyond the number of slots are left unassigned.
+pub fn assign_slots(
+    slots: &[(SquadRole, IVec2)],
+    members: &[(Entity, IVec2)],
+) -> Vec<(Entity, IVec2)> {
+    let mut unassigned: Vec<(Entity, IVec2)> = members.to_vec();
+    let mut assignments = vec![];
+
+    for (_, slot) in slots {
+        let closest = unassigned
+            .iter()
+            .enumerate()
+            .min_by_key(|(_, (_, pos))| chessboard_distance(pos, slot))
+            .map(|(index, _)| index);
+
+        if let Some(index) = closest {
+            let (member, _) = unassigned.swap_remove(index);
+            assignments.push((member, *slot));
+        }
+    }
+
+    assignments
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::{math::IVec2, prelude::World};
+
+    use crate::core::types::Cardinal;
+
+    use super::{assign_slots, formation_slots, Squad, SquadRole};
+
+    #[test]
+    fn slots_follow_target_facing() {
+        let slots = formation_slots(&IVec2::ZERO, Cardinal::East);
+
+        assert_eq!((SquadRole::Front, IVec2::new(2, 0)), slots[0]);
+        assert_eq!((SquadRole::Behind, IVec2::new(-2, 0)), slots[1]);
+        assert_eq!((SquadRole::LeftFlank, IVec2::new(0, -2)), slots[2]);
+        assert_eq!((SquadRole::RightFlank, IVec2::new(0, 2)), slots[3]);
+    }
+
+    #[test]
+    fn closest_members_take_priority_slots() {
+        let slots = formation_slots(&IVec2::ZERO, Cardinal::North);
+        let mut world = World::new();
+        let near_front = world.spawn().id();
+        let near_back = world.spawn().id();
+        let members = [
+            (near_back, IVec2::new(0, 5)),
+            (near_front, IVec2::new(1, -3)),
+        ];
+
+        let assignments = assign_slots(&slots, &members);
+
+        assert_eq!(2, assignments.len());
+        assert!(assignments.contains(&(near_front, IVec2::new(0, -2))));
+        assert!(assignments.contains(&(near_back, IVec2::new(0, 2))));
+    }
+
+    #[test]
+    fn windups_are_staggered() {
+        let mut squad = Squad::default();
+        assert!(squad.can_wind_up(0));
+
+        squad.last_windup = Some(100);
+        assert!(!squad.can_wind_up(110));
+        assert!(squad.can_wind_up(130));
+    }
+}
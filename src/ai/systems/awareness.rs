@@ -0,0 +1,211 @@
+use bevy::prelude::*;
+
+use crate::{
+    actors::{Actor, Player},
+    ai::{
+        constants::{
+            AWARENESS_DECAY_RATE, DARKVISION_EXPOSURE, NOTICE_DISTANCE, PERIPHERAL_FOCUS,
+            SIGHT_AWARENESS_RATE,
+        },
+        Awareness, AwarenessState, Monster,
+    },
+    core::{
+        types::{Cardinal, Facing, GridPos, Increment, Real},
+        TimeIncrementEvent,
+    },
+    util::{algorithms::geometry::chessboard_distance, helpers::GridRotator},
+    world::{Darkvision, ViewModifier, Viewshed, WorldMap, FULL_LIGHT},
+};
+
+/// How plainly the viewer at `origin` sees the player at `target`, from 0 to 1.
+/// Closer, better lit and more central in the view is plainer.
+fn exposure(
+    world_map: &WorldMap,
+    origin: &IVec2,
+    facing: Cardinal,
+    viewshed: &Viewshed,
+    darkvision: Option<&Darkvision>,
+    target: &IVec2,
+) -> Real {
+    let distance = chessboard_distance(origin, target);
+    let reach = viewshed.effective_fov().range().min(NOTICE_DISTANCE);
+    let nearness = (1.0 - distance as Real / (reach + 1) as Real).max(0.0);
+
+    let mut light = world_map.light_at(target).intensity / FULL_LIGHT;
+    if darkvision.map_or(false, |darkvision| distance <= darkvision.range) {
+        light = light.max(DARKVISION_EXPOSURE);
+    }
+
+    let local = Facing::from(facing).rot_f(&(*target - *origin).as_vec2());
+    let centrality = local.normalize_or_zero().y.max(0.0);
+    let focus = PERIPHERAL_FOCUS + (1.0 - PERIPHERAL_FOCUS) * centrality;
+
+    nearness * light * focus
+}
+
+/// Builds up each monster's awareness while it sees the player and lets it fade otherwise.
+/// Alerted monsters watch every direction.
+pub fn update_awareness(
+    world_map: Res<WorldMap>,
+    mut time_events: EventReader<TimeIncrementEvent>,
+    player: Query<(Entity, &GridPos), With<Player>>,
+    mut monsters: Query<
+        (
+            &GridPos,
+            &Actor,
+            &mut Viewshed,
+            Option<&Darkvision>,
+            &mut Awareness,
+        ),
+        With<Monster>,
+    >,
+) {
+    let elapsed: Increment = time_events.iter().map(|event| event.delta_time).sum();
+    let player = player.iter().next();
+
+    for (pos, actor, mut viewshed, darkvision, mut awareness) in monsters.iter_mut() {
+        if elapsed > 0 {
+            match player {
+                Some((player, player_pos)) if viewshed.visible_tiles.contains(&player_pos.0) => {
+                    let exposure = exposure(
+                        &world_map,
+                        &pos.0,
+                        actor.facing,
+                        &viewshed,
+                        darkvision,
+                        &player_pos.0,
+                    );
+                    awareness.raise(
+                        SIGHT_AWARENESS_RATE * exposure * elapsed as Real,
+                        player,
+                        player_pos.0,
+                    );
+                }
+                _ => awareness.decay(AWARENESS_DECAY_RATE * elapsed as Real),
+            }
+        }
+
+        let is_alerted = awareness.state() == AwarenessState::Alerted;
+        if is_alerted != viewshed.modifiers.contains(&ViewModifier::Alert) {
+            if is_alerted {
+                viewshed.add_modifier(ViewModifier::Alert);
+            } else {
+                viewshed.remove_modifier(ViewModifier::Alert);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::prelude::*;
+
+    use crate::{
+        actors::{Actor, ActorBundle, Player},
+        ai::{Awareness, AwarenessState, Monster},
+        core::{
+            types::{Cardinal, GridPos, Int, Real},
+            TimeIncrementEvent,
+        },
+        test::helpers::{
+            visibility::from_ascii_layout,
+            world::{pass_time, world_with_area},
+        },
+        util::algorithms::field_of_view::FOV,
+        world::{systems::update_viewsheds, ViewModifier, Viewshed, FULL_LIGHT},
+    };
+
+    use super::update_awareness;
+
+    /// A monster at the bottom of a hall facing north, with the player `ahead` tiles in front.
+    fn hall(ambient_light: Real, ahead: Int) -> (World, Entity) {
+        let (origin, mut area) = from_ascii_layout(
+            "#######
+             #.....#
+             #.....#
+             #.....#
+             #.....#
+             #..@..#
+             #######",
+        );
+        area.ambient_light = ambient_light;
+        let mut world = world_with_area(area);
+        world.insert_resource(Events::<TimeIncrementEvent>::default());
+        world
+            .spawn()
+            .insert(Player)
+            .insert(GridPos(origin - IVec2::new(0, ahead)));
+        let monster = world
+            .spawn()
+            .insert(Monster {
+                kind: "goblin".to_string(),
+            })
+            .insert(Awareness::default())
+            .insert_bundle(ActorBundle {
+                position: GridPos(origin),
+                actor: Actor {
+                    facing: Cardinal::North,
+                    ..Default::default()
+                },
+                viewshed: Viewshed::with_fov(FOV::Cone(8, 1.0)),
+                ..Default::default()
+            })
+            .id();
+        SystemStage::single(update_viewsheds.system()).run(&mut world);
+        (world, monster)
+    }
+
+    #[test]
+    fn a_lit_player_in_view_alerts_the_monster() {
+        let (mut world, monster) = hall(FULL_LIGHT, 2);
+        let mut stage = SystemStage::single(update_awareness.system());
+
+        pass_time(&mut world, &mut stage, 10);
+        let awareness = *world.get::<Awareness>(monster).unwrap();
+        assert!(awareness.level > 0.0);
+        assert_eq!(AwarenessState::Unaware, awareness.state());
+
+        for _ in 0..10 {
+            pass_time(&mut world, &mut stage, 30);
+        }
+        let awareness = world.get::<Awareness>(monster).unwrap();
+        assert_eq!(AwarenessState::Alerted, awareness.state());
+        assert!(world
+            .get::<Viewshed>(monster)
+            .unwrap()
+            .modifiers
+            .contains(&ViewModifier::Alert));
+    }
+
+    #[test]
+    fn the_player_stays_hidden_in_the_dark() {
+        let (mut world, monster) = hall(0.0, 2);
+        let mut stage = SystemStage::single(update_awareness.system());
+
+        for _ in 0..10 {
+            pass_time(&mut world, &mut stage, 30);
+        }
+        assert_eq!(0.0, world.get::<Awareness>(monster).unwrap().level);
+    }
+
+    #[test]
+    fn distance_slows_detection() {
+        let (mut near, near_monster) = hall(FULL_LIGHT, 1);
+        let (mut far, far_monster) = hall(FULL_LIGHT, 4);
+
+        pass_time(
+            &mut near,
+            &mut SystemStage::single(update_awareness.system()),
+            30,
+        );
+        pass_time(
+            &mut far,
+            &mut SystemStage::single(update_awareness.system()),
+            30,
+        );
+        assert!(
+            near.get::<Awareness>(near_monster).unwrap().level
+                > far.get::<Awareness>(far_monster).unwrap().level
+        );
+    }
+}
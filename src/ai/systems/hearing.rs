@@ -0,0 +1,39 @@
+use bevy::prelude::*;
+
+use crate::{
+    actors::Player,
+    ai::{constants::NOISE_AWARENESS, Awareness, HeardNoise, Hearing},
+    core::types::{GridPos, Real},
+    world::{propagate_noise, NoiseEvent, WorldMap},
+};
+
+/// Lets listeners hear noises. Noises made where the player stands also make
+/// listeners more aware of them, the louder the more.
+pub fn hear_noises(
+    world_map: Res<WorldMap>,
+    mut noise_events: EventReader<NoiseEvent>,
+    player: Query<(Entity, &GridPos), With<Player>>,
+    mut listeners: Query<(&GridPos, &mut Hearing, Option<&mut Awareness>)>,
+) {
+    let player = player.iter().next();
+
+    for noise in noise_events.iter() {
+        let heard = propagate_noise(&world_map, noise);
+        let made_by_player = player.filter(|(_, player_pos)| player_pos.0 == noise.origin);
+
+        for (pos, mut hearing, awareness) in listeners.iter_mut() {
+            if pos.0 == noise.origin {
+                continue;
+            }
+            if let Some(loudness) = heard.get(&pos.0) {
+                hearing.hear(HeardNoise {
+                    origin: noise.origin,
+                    loudness: *loudness,
+                });
+                if let (Some((player, _)), Some(mut awareness)) = (made_by_player, awareness) {
+                    awareness.raise(NOISE_AWARENESS * *loudness as Real, player, noise.origin);
+                }
+            }
+        }
+    }
+}
@@ -1,3 +1,14 @@
+mod awareness;
+pub use awareness::*;
+
+mod hearing;
+pub use hearing::*;
+
 mod monster_ai;
+pub use monster_ai::*;
+
+mod squad;
+pub use squad::*;
 
-pub use monster_ai::*;
\ No newline at end of file
+mod utility_ai;
+pub use utility_ai::*;
@@ -1,25 +1,135 @@
 use bevy::prelude::*;
 
 use crate::{
-    actors::{Action, Activity, Player},
-    ai::Monster,
-    core::types::GridPos,
-    world::Viewshed,
+    actors::{
+        constants::{ATTACK_WINDUP, MOVE_TIME},
+        Action, Activity, Actor,
+    },
+    ai::{
+        constants::ATTACK_REACH,
+        navigation::{blocks_walker, path_towards, step_towards},
+        targeting::closest_visible_hostile,
+        Awareness, Faction, FactionRelations, Hearing, Monster, SquadMember, UtilityAI,
+    },
+    core::types::{Cardinal, GridPos},
+    util::algorithms::{
+        geometry::{cardinal_towards, chessboard_distance},
+        line::has_line_of_sight,
+    },
+    world::{OpensDoors, Viewshed, WorldMap},
 };
 
 pub fn monster_ai(
     mut commands: Commands,
-    mut monster_query: Query<(Entity, &Viewshed), (With<Monster>, Without<Activity>)>,
-    player_query: Query<&GridPos, With<Player>>,
+    world_map: Res<WorldMap>,
+    relations: Res<FactionRelations>,
+    mut monster_query: Query<
+        (
+            Entity,
+            &Actor,
+            &GridPos,
+            &Viewshed,
+            &Faction,
+            Option<&Awareness>,
+            Option<&mut Hearing>,
+            Option<&OpensDoors>,
+        ),
+        (
+            With<Monster>,
+            Without<Activity>,
+            Without<UtilityAI>,
+            Without<SquadMember>,
+        ),
+    >,
+    targets: Query<(Entity, &GridPos, &Faction), With<Actor>>,
 ) {
-    for (monster, viewshed) in monster_query.iter_mut() {
-        for player_pos in player_query.iter() {
-            if viewshed.visible_tiles.contains(&player_pos.0) {
-                commands.entity(monster).insert(Activity {
-                    time_to_complete: 60,
-                    action: Action::InitiateAttack,
-                });
+    for (monster, actor, pos, viewshed, faction, awareness, mut hearing, opens_doors) in
+        monster_query.iter_mut()
+    {
+        let is_blocked = |pos: &IVec2| blocks_walker(&world_map, pos, opens_doors.is_some());
+        let target = closest_visible_hostile(
+            monster,
+            &pos.0,
+            faction,
+            viewshed,
+            awareness,
+            &relations,
+            targets.iter(),
+        )
+        .map(|(_, target_pos)| target_pos);
+
+        if let Some(target) = target {
+            if let Some(hearing) = hearing.as_mut() {
+                hearing.heard = None;
             }
+            let is_clear = has_line_of_sight(&pos.0, &target, &|pos| world_map.is_blocking(pos));
+            commands.entity(monster).insert(engage(
+                &pos.0,
+                actor.facing,
+                &target,
+                is_clear,
+                &is_blocked,
+            ));
+        } else if let Some(activity) = investigate(
+            &pos.0,
+            actor.facing,
+            awareness,
+            hearing.as_deref_mut(),
+            &is_blocked,
+        ) {
+            commands.entity(monster).insert(activity);
+        }
+    }
+}
+
+/// Goes to check on whatever made the monster suspicious, or failing that the noise
+/// it heard. The noise is forgotten once reached, or if there is no way to it.
+pub fn investigate(
+    pos: &IVec2,
+    facing: Cardinal,
+    awareness: Option<&Awareness>,
+    hearing: Option<&mut Hearing>,
+    is_blocked: &dyn Fn(&IVec2) -> bool,
+) -> Option<Activity> {
+    let action = if let Some(suspected) = awareness.and_then(Awareness::suspected_position) {
+        // Looks towards whatever caught its attention before going to check.
+        path_towards(pos, facing, &suspected, is_blocked)
+    } else {
+        let hearing = hearing?;
+        let noise = hearing.heard?;
+        let action = path_towards(pos, facing, &noise.origin, is_blocked);
+        if action.is_none() {
+            hearing.heard = None;
+        }
+        action
+    }?;
+    Some(Activity {
+        time_to_complete: MOVE_TIME,
+        action,
+    })
+}
+
+/// Winds up an attack once facing the target within reach, with nothing in between
+/// to take the blow. Otherwise keeps closing in, around whatever is in the way.
+fn engage(
+    pos: &IVec2,
+    facing: Cardinal,
+    target: &IVec2,
+    is_clear: bool,
+    is_blocked: &dyn Fn(&IVec2) -> bool,
+) -> Activity {
+    let is_facing_target = cardinal_towards(pos, target) == Some(facing);
+    if is_facing_target && chessboard_distance(pos, target) <= ATTACK_REACH && is_clear {
+        Activity {
+            time_to_complete: ATTACK_WINDUP,
+            action: Action::InitiateAttack,
+        }
+    } else {
+        Activity {
+            time_to_complete: MOVE_TIME,
+            action: path_towards(pos, facing, target, is_blocked)
+                .or_else(|| step_towards(pos, facing, target))
+                .unwrap_or_default(),
         }
     }
 }
@@ -0,0 +1,246 @@
+use bevy::prelude::*;
+
+use crate::{
+    actors::{
+        constants::{ATTACK_WINDUP, MOVE_TIME},
+        Action, Activity, Actor,
+    },
+    ai::{
+        constants::ATTACK_REACH,
+        navigation::{blocks_walker, path_towards, relative_direction},
+        squad::{assign_slots, formation_slots},
+        systems::investigate,
+        targeting::closest_visible_hostile,
+        Awareness, Faction, FactionRelations, Hearing, Monster, Squad, SquadMember,
+    },
+    core::{types::GridPos, IncrementalClock},
+    util::algorithms::{
+        geometry::{cardinal_towards, chessboard_distance},
+        line::has_line_of_sight,
+    },
+    world::{OpensDoors, Viewshed, WorldMap},
+};
+
+pub fn share_squad_knowledge(
+    relations: Res<FactionRelations>,
+    members: Query<(
+        Entity,
+        &SquadMember,
+        &GridPos,
+        &Viewshed,
+        &Faction,
+        Option<&Awareness>,
+    )>,
+    actors: Query<(Entity, &GridPos, &Faction), With<Actor>>,
+    facings: Query<&Actor>,
+    mut squads: Query<&mut Squad>,
+) {
+    for mut squad in squads.iter_mut() {
+        if let Some(target) = squad.target {
+            if actors.get(target).is_err() {
+                *squad = Squad {
+                    last_windup: squad.last_windup,
+                    ..Default::default()
+                };
+            }
+        }
+    }
+
+    for (member, squad_member, pos, viewshed, faction, awareness) in members.iter() {
+        let sighting = closest_visible_hostile(
+            member,
+            &pos.0,
+            faction,
+            viewshed,
+            awareness,
+            &relations,
+            actors.iter(),
+        );
+        let (target, target_pos) = match sighting {
+            Some(sighting) => sighting,
+            None => continue,
+        };
+        if let Ok(mut squad) = squads.get_mut(squad_member.squad) {
+            if squad.target.is_none() || squad.target == Some(target) {
+                squad.target = Some(target);
+                squad.last_known_position = Some(target_pos);
+                squad.last_known_facing = facings.get(target).ok().map(|actor| actor.facing);
+            }
+        }
+    }
+}
+
+pub fn assign_squad_slots(
+    world_map: Res<WorldMap>,
+    squads: Query<(Entity, &Squad)>,
+    mut members: Query<(Entity, &mut SquadMember, &GridPos)>,
+) {
+    for (squad_entity, squad) in squads.iter() {
+        let slots = match (squad.last_known_position, squad.last_known_facing) {
+            (Some(target_pos), Some(facing)) => formation_slots(&target_pos, facing)
+                .into_iter()
+                .filter(|(_, slot)| !world_map.is_blocking(slot))
+                .collect(),
+            _ => vec![],
+        };
+
+        let squad_members: Vec<(Entity, IVec2)> = members
+            .iter()
+            .filter(|(_, member, _)| member.squad == squad_entity)
+            .map(|(entity, _, pos)| (entity, pos.0))
+            .collect();
+        let assignments = assign_slots(&slots, &squad_members);
+
+        for (entity, _) in squad_members {
+            if let Ok((_, mut member, _)) = members.get_mut(entity) {
+                member.slot = assignments
+                    .iter()
+                    .find(|(assigned, _)| *assigned == entity)
+                    .map(|(_, slot)| *slot);
+            }
+        }
+    }
+}
+
+/// Closes in on the squad's target together. Until the squad has one, members
+/// investigate what they sense on their own, like lone monsters.
+pub fn squad_ai(
+    mut commands: Commands,
+    world_map: Res<WorldMap>,
+    clock: Res<IncrementalClock>,
+    mut squads: Query<&mut Squad>,
+    mut members: Query<
+        (
+            Entity,
+            &Actor,
+            &GridPos,
+            &SquadMember,
+            Option<&Awareness>,
+            Option<&mut Hearing>,
+            Option<&OpensDoors>,
+        ),
+        (With<Monster>, Without<Activity>),
+    >,
+) {
+    for (entity, actor, pos, member, awareness, mut hearing, opens_doors) in members.iter_mut() {
+        let mut squad = match squads.get_mut(member.squad) {
+            Ok(squad) => squad,
+            Err(_) => continue,
+        };
+        let is_blocked = |pos: &IVec2| blocks_walker(&world_map, pos, opens_doors.is_some());
+        let target = match squad.last_known_position {
+            Some(target) => target,
+            None => {
+                if let Some(activity) = investigate(
+                    &pos.0,
+                    actor.facing,
+                    awareness,
+                    hearing.as_deref_mut(),
+                    &is_blocked,
+                ) {
+                    commands.entity(entity).insert(activity);
+                }
+                continue;
+            }
+        };
+
+        let is_facing_target = cardinal_towards(&pos.0, &target) == Some(actor.facing);
+        let is_in_reach = chessboard_distance(&pos.0, &target) <= ATTACK_REACH
+            && has_line_of_sight(&pos.0, &target, &|pos| world_map.is_blocking(pos));
+
+        let action = if is_facing_target && is_in_reach && squad.can_wind_up(clock.time) {
+            squad.last_windup = Some(clock.time);
+            Action::InitiateAttack
+        } else if let Some(slot) = member.slot.filter(|slot| *slot != pos.0) {
+            path_towards(&pos.0, actor.facing, &slot, &is_blocked).unwrap_or(Action::Wait)
+        } else {
+            match cardinal_towards(&pos.0, &target) {
+                Some(cardinal) if cardinal != actor.facing => {
+                    Action::Turn(relative_direction(actor.facing, cardinal))
+                }
+                _ => Action::Wait,
+            }
+        };
+
+        commands.entity(entity).insert(Activity {
+            time_to_complete: if action == Action::InitiateAttack {
+                ATTACK_WINDUP
+            } else {
+                MOVE_TIME
+            },
+            action,
+        });
+    }
+}
+
+/// Despawns squads whose last member has died.
+pub fn disband_empty_squads(
+    mut commands: Commands,
+    squads: Query<Entity, With<Squad>>,
+    members: Query<&SquadMember>,
+) {
+    for squad in squads.iter() {
+        if !members.iter().any(|member| member.squad == squad) {
+            commands.entity(squad).despawn();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::prelude::*;
+
+    use crate::{
+        actors::{Action, Activity, Actor, ActorBundle},
+        ai::{navigation::relative_direction, Monster, Squad, SquadMember},
+        core::{
+            types::{Cardinal, GridPos},
+            IncrementalClock,
+        },
+        test::helpers::world::world_from_ascii_layout,
+    };
+
+    use super::squad_ai;
+
+    #[test]
+    fn members_walk_around_walls_to_their_slot() {
+        let (mut world, start) = world_from_ascii_layout(
+            "#####
+             #@#.#
+             #...#
+             #####",
+        );
+        world.insert_resource(IncrementalClock::default());
+        let slot = start + IVec2::new(2, 0);
+        let squad = world
+            .spawn()
+            .insert(Squad {
+                last_known_position: Some(start + IVec2::Y),
+                ..Default::default()
+            })
+            .id();
+        let member = world
+            .spawn()
+            .insert(Monster {
+                kind: "goblin".to_string(),
+            })
+            .insert(SquadMember {
+                squad,
+                slot: Some(slot),
+            })
+            .insert_bundle(ActorBundle {
+                position: GridPos(start),
+                ..Default::default()
+            })
+            .id();
+        world.get_mut::<Actor>(member).unwrap().facing = Cardinal::East;
+
+        SystemStage::single(squad_ai.system()).run(&mut world);
+
+        let activity = world.get::<Activity>(member).unwrap();
+        assert_eq!(
+            Action::Turn(relative_direction(Cardinal::East, Cardinal::SouthEast)),
+            activity.action
+        );
+    }
+}
@@ -0,0 +1,118 @@
+use bevy::prelude::*;
+
+use crate::{
+    actors::{effects::Telegraph, Action, Activity, Actor},
+    ai::{
+        navigation::blocks_walker,
+        targeting::closest_visible_hostile,
+        utility::{best_action, score_actions, Situation, UtilityAI},
+        Awareness, Faction, FactionRelations, Hearing, Monster, Relation, SquadMember,
+    },
+    core::types::GridPos,
+    world::{OpensDoors, Viewshed, WorldMap},
+};
+
+pub fn utility_ai(
+    mut commands: Commands,
+    world_map: Res<WorldMap>,
+    relations: Res<FactionRelations>,
+    mut monster_query: Query<
+        (
+            Entity,
+            &Monster,
+            &Actor,
+            &GridPos,
+            &Viewshed,
+            &Faction,
+            &UtilityAI,
+            Option<&Awareness>,
+            Option<&mut Hearing>,
+            Option<&OpensDoors>,
+        ),
+        (Without<Activity>, Without<SquadMember>),
+    >,
+    actors: Query<(Entity, &GridPos, &Faction), With<Actor>>,
+    telegraphs: Query<(&GridPos, &Telegraph)>,
+) {
+    for (
+        entity,
+        monster,
+        actor,
+        pos,
+        viewshed,
+        faction,
+        utility,
+        awareness,
+        mut hearing,
+        opens_doors,
+    ) in monster_query.iter_mut()
+    {
+        let mut target = closest_visible_hostile(
+            entity,
+            &pos.0,
+            faction,
+            viewshed,
+            awareness,
+            &relations,
+            actors.iter(),
+        )
+        .map(|(_, target_pos)| target_pos)
+        .or_else(|| awareness.and_then(Awareness::suspected_position));
+        if let Some(hearing) = hearing.as_mut() {
+            if target.is_some() || hearing.heard.map(|noise| noise.origin) == Some(pos.0) {
+                hearing.heard = None;
+            }
+            target = target.or_else(|| hearing.heard.map(|noise| noise.origin));
+        }
+
+        let threats: Vec<IVec2> = telegraphs
+            .iter()
+            .filter(|(_, telegraph)| telegraph.attacker != entity)
+            .filter(|(_, telegraph)| match actors.get(telegraph.attacker) {
+                Ok((_, _, attacker_faction)) => {
+                    relations.relation(faction, attacker_faction) != Relation::Allied
+                }
+                Err(_) => true,
+            })
+            .map(|(telegraph_pos, _)| telegraph_pos.0)
+            .collect();
+
+        let allies: Vec<IVec2> = actors
+            .iter()
+            .filter(|(other, _, other_faction)| {
+                *other != entity && relations.relation(faction, other_faction) == Relation::Allied
+            })
+            .map(|(_, ally_pos, _)| ally_pos.0)
+            .collect();
+
+        let situation = Situation {
+            position: pos.0,
+            facing: actor.facing,
+            health: actor.properties.health.value() / 100.0,
+            target,
+            threats: &threats,
+            allies: &allies,
+        };
+        let scores = score_actions(&situation, &utility.weights, &|pos| {
+            blocks_walker(&world_map, pos, opens_doors.is_some())
+        });
+        let action = best_action(&scores);
+
+        debug!(
+            "{} {:?} at {:?} picks {:?}",
+            monster.kind, entity, pos.0, action
+        );
+        for (candidate, score) in &scores {
+            debug!("    {:?}: {:.3}", candidate, score);
+        }
+
+        commands.entity(entity).insert(Activity {
+            time_to_complete: if action == Action::InitiateAttack {
+                60
+            } else {
+                30
+            },
+            action,
+        });
+    }
+}
@@ -0,0 +1,29 @@
+use bevy::{math::IVec2, prelude::Entity};
+
+use crate::{
+    ai::{Awareness, Faction, FactionRelations},
+    core::types::GridPos,
+    util::algorithms::geometry::chessboard_distance,
+    world::Viewshed,
+};
+
+/// Closest visible actor hostile to `faction`, along with its position.
+/// With `awareness`, the player is only a target once the seeker is alerted to them.
+pub fn closest_visible_hostile<'a>(
+    seeker: Entity,
+    pos: &IVec2,
+    faction: &Faction,
+    viewshed: &Viewshed,
+    awareness: Option<&Awareness>,
+    relations: &FactionRelations,
+    candidates: impl Iterator<Item = (Entity, &'a GridPos, &'a Faction)>,
+) -> Option<(Entity, IVec2)> {
+    candidates
+        .filter(|(other, _, other_faction)| {
+            *other != seeker && relations.is_hostile(faction, other_faction)
+        })
+        .filter(|(_, target_pos, _)| viewshed.visible_tiles.contains(&target_pos.0))
+        .filter(|(other, _, _)| awareness.map_or(true, |awareness| awareness.notices(*other)))
+        .min_by_key(|(_, target_pos, _)| chessboard_distance(pos, &target_pos.0))
+        .map(|(target, target_pos, _)| (target, target_pos.0))
+}
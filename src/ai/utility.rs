@@ -0,0 +1,227 @@
+use bevy::{math::IVec2, prelude::Component};
+use serde::Deserialize;
+
+use crate::{
+    actors::{attack_pattern, do_move, rotate_facing, Action},
+    core::types::{Cardinal, Direction, Int, Predicate, Real},
+    util::algorithms::geometry::{
+        cardinal_towards, chessboard_distance, chessboard_rotate_and_place,
+    },
+};
+
+/// Distance beyond which a target is considered as far as it can get.
+const DISTANCE_SCALE: Real = 10.0;
+const ALLY_RADIUS: Int = 2;
+const MAX_COUNTED_ALLIES: Real = 3.0;
+
+/// How much each consideration matters to a monster type. Negative weights invert a preference.
+#[derive(Debug, Clone, Copy, Default, Deserialize)]
+pub struct UtilityWeights {
+    /// Being close to the target.
+    pub approach: Real,
+    /// Facing the target.
+    pub facing: Real,
+    /// Starting an attack that would reach the target.
+    pub attack: Real,
+    /// Standing on tiles telegraphed by someone else's attack.
+    pub threat: Real,
+    /// Keeping away from the target, scaled by missing health.
+    pub caution: Real,
+    /// Staying near allies.
+    pub allies: Real,
+    /// Waiting in place.
+    pub idle: Real,
+}
+
+#[derive(Debug, Clone, Copy, Component)]
+pub struct UtilityAI {
+    pub weights: UtilityWeights,
+}
+
+/// What a monster knows when scoring its options.
+pub struct Situation<'a> {
+    pub position: IVec2,
+    pub facing: Cardinal,
+    /// Remaining health, from 0 to 1.
+    pub health: Real,
+    pub target: Option<IVec2>,
+    pub threats: &'a [IVec2],
+    pub allies: &'a [IVec2],
+}
+
+pub fn candidate_actions() -> Vec<Action> {
+    let mut actions = vec![Action::Wait, Action::InitiateAttack];
+    for index in 0..8 {
+        let direction: Direction = index.into();
+        actions.push(Action::Move(direction));
+        if direction != Direction::Forward {
+            actions.push(Action::Turn(direction));
+        }
+    }
+    actions
+}
+
+pub fn score_actions(
+    situation: &Situation,
+    weights: &UtilityWeights,
+    is_blocking: &Predicate<IVec2>,
+) -> Vec<(Action, Real)> {
+    candidate_actions()
+        .into_iter()
+        .map(|action| (action, score(action, situation, weights, is_blocking)))
+        .collect()
+}
+
+pub fn best_action(scores: &[(Action, Real)]) -> Action {
+    scores
+        .iter()
+        .fold(
+            None,
+            |best: Option<(Action, Real)>, (action, score)| match best {
+                Some((_, best_score)) if best_score >= *score => best,
+                _ => Some((*action, *score)),
+            },
+        )
+        .map(|(action, _)| action)
+        .unwrap_or_default()
+}
+
+fn predict(
+    action: Action,
+    situation: &Situation,
+    is_blocking: &Predicate<IVec2>,
+) -> (IVec2, Cardinal) {
+    match action {
+        Action::Move(direction) => (
+            do_move(
+                &situation.position,
+                direction,
+                situation.facing,
+                is_blocking,
+            ),
+            situation.facing,
+        ),
+        Action::Turn(direction) => (
+            situation.position,
+            rotate_facing(situation.facing, direction.into()),
+        ),
+        _ => (situation.position, situation.facing),
+    }
+}
+
+fn score(
+    action: Action,
+    situation: &Situation,
+    weights: &UtilityWeights,
+    is_blocking: &Predicate<IVec2>,
+) -> Real {
+    let (position, facing) = predict(action, situation, is_blocking);
+    let mut score = 0.0;
+
+    if let Some(target) = situation.target {
+        let distance = chessboard_distance(&position, &target) as Real;
+        let closeness = 1.0 - (distance / DISTANCE_SCALE).min(1.0);
+        score += weights.approach * closeness;
+        score -= weights.caution * (1.0 - situation.health) * closeness;
+
+        if cardinal_towards(&position, &target) == Some(facing) {
+            score += weights.facing;
+        }
+        if action == Action::InitiateAttack
+            && chessboard_rotate_and_place(&position, &attack_pattern(), facing.into())
+                .contains(&target)
+        {
+            score += weights.attack;
+        }
+    }
+
+    if situation.threats.contains(&position) {
+        score -= weights.threat;
+    }
+
+    let nearby_allies = situation
+        .allies
+        .iter()
+        .filter(|ally| chessboard_distance(&position, ally) <= ALLY_RADIUS)
+        .count() as Real;
+    score += weights.allies * nearby_allies.min(MAX_COUNTED_ALLIES) / MAX_COUNTED_ALLIES;
+
+    if action == Action::Wait {
+        score += weights.idle;
+    }
+
+    score
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::math::IVec2;
+
+    use crate::{
+        actors::Action,
+        core::types::{Cardinal, Direction},
+    };
+
+    use super::{best_action, score_actions, Situation, UtilityWeights};
+
+    fn weights() -> UtilityWeights {
+        UtilityWeights {
+            approach: 1.0,
+            facing: 0.5,
+            attack: 2.0,
+            threat: 3.0,
+            caution: 0.0,
+            allies: 0.0,
+            idle: 0.1,
+        }
+    }
+
+    #[test]
+    fn attacks_target_in_reach() {
+        let situation = Situation {
+            position: IVec2::ZERO,
+            facing: Cardinal::North,
+            health: 1.0,
+            target: Some(IVec2::new(0, -2)),
+            threats: &[],
+            allies: &[],
+        };
+        let scores = score_actions(&situation, &weights(), &|_| false);
+
+        assert_eq!(Action::InitiateAttack, best_action(&scores));
+    }
+
+    #[test]
+    fn turns_towards_target_behind() {
+        let situation = Situation {
+            position: IVec2::ZERO,
+            facing: Cardinal::North,
+            health: 1.0,
+            target: Some(IVec2::new(0, 2)),
+            threats: &[],
+            allies: &[],
+        };
+        let scores = score_actions(&situation, &weights(), &|_| false);
+
+        assert_eq!(Action::Turn(Direction::Back), best_action(&scores));
+    }
+
+    #[test]
+    fn steps_out_of_telegraphed_tiles() {
+        let threats = [IVec2::new(5, 5), IVec2::new(5, 4), IVec2::new(5, 6)];
+        let situation = Situation {
+            position: IVec2::new(5, 5),
+            facing: Cardinal::North,
+            health: 1.0,
+            target: None,
+            threats: &threats,
+            allies: &[],
+        };
+        let scores = score_actions(&situation, &weights(), &|_| false);
+
+        match best_action(&scores) {
+            Action::Move(_) => (),
+            action => panic!("Expected to move out of danger, got {:?}", action),
+        }
+    }
+}
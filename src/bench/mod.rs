@@ -0,0 +1,3 @@
+extern crate test;
+
+mod visibility;
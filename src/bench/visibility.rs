@@ -0,0 +1,127 @@
+use bevy::math::IVec2;
+
+use crate::{
+    core::types::Cardinal,
+    test::helpers::visibility::{cases, from_ascii_layout},
+    util::{
+        algorithms::{field_of_view::FOV, reference, shadowcast, symmetric_shadowcasting},
+        helpers::GridSet,
+    },
+    world::WorldMap,
+};
+
+use super::test::{black_box, Bencher};
+
+struct Case {
+    origin: IVec2,
+    world_map: WorldMap,
+    fov: FOV,
+    cardinal: Cardinal,
+}
+
+/// Every map from the visibility test cases, seen from its '@'.
+fn maps() -> Vec<Case> {
+    cases()
+        .map(|case| {
+            let (origin, area) = from_ascii_layout(&case.layout);
+            let mut world_map = WorldMap::default();
+            world_map.insert_offset(&IVec2::ZERO, area);
+            Case {
+                origin,
+                world_map,
+                fov: FOV::Quadratic(case.range, case.a, case.b),
+                cardinal: case.cardinal,
+            }
+        })
+        .collect()
+}
+
+fn as_vec(case: &Case) -> Vec<IVec2> {
+    symmetric_shadowcasting(
+        &case.origin,
+        &|pos| case.fov.sees(pos, case.cardinal),
+        &|pos| case.world_map.is_opaque(pos),
+    )
+}
+
+fn as_grid_set(case: &Case) -> GridSet {
+    let (min, max) = case.world_map.bounds().unwrap();
+    let mut visible = GridSet::new(min, max);
+    shadowcast(
+        &case.origin,
+        &mut |pos| visible.insert(*pos),
+        &|pos| case.fov.sees(pos, case.cardinal),
+        &|pos| case.world_map.is_opaque(pos),
+    );
+    visible
+}
+
+/// Every tile of the case's map, as an AI checking targets would ask about them.
+fn all_tiles(case: &Case) -> Vec<IVec2> {
+    let (min, max) = case.world_map.bounds().unwrap();
+    (min.y..=max.y)
+        .flat_map(|y| (min.x..=max.x).map(move |x| IVec2::new(x, y)))
+        .collect()
+}
+
+#[bench]
+fn recompute_with_fractions(bencher: &mut Bencher) {
+    let maps = maps();
+    bencher.iter(|| {
+        for case in &maps {
+            black_box(reference::symmetric_shadowcasting(
+                &case.origin,
+                &|pos| case.fov.sees(pos, case.cardinal),
+                &|pos| case.world_map.is_opaque(pos),
+            ));
+        }
+    });
+}
+
+#[bench]
+fn recompute_as_vec(bencher: &mut Bencher) {
+    let maps = maps();
+    bencher.iter(|| {
+        for case in &maps {
+            black_box(as_vec(case));
+        }
+    });
+}
+
+#[bench]
+fn recompute_as_grid_set(bencher: &mut Bencher) {
+    let maps = maps();
+    bencher.iter(|| {
+        for case in &maps {
+            black_box(as_grid_set(case));
+        }
+    });
+}
+
+#[bench]
+fn lookup_in_vec(bencher: &mut Bencher) {
+    let maps = maps();
+    let lookups: Vec<(Vec<IVec2>, Vec<IVec2>)> = maps
+        .iter()
+        .map(|case| (as_vec(case), all_tiles(case)))
+        .collect();
+    bencher.iter(|| {
+        for (visible, tiles) in &lookups {
+            black_box(tiles.iter().filter(|pos| visible.contains(pos)).count());
+        }
+    });
+}
+
+#[bench]
+fn lookup_in_grid_set(bencher: &mut Bencher) {
+    let maps = maps();
+    let lookups: Vec<(GridSet, Vec<IVec2>)> = maps
+        .iter()
+        .map(|case| (as_grid_set(case), all_tiles(case)))
+        .collect();
+    bencher.iter(|| {
+        for (visible, tiles) in &lookups {
+            black_box(tiles.iter().filter(|pos| visible.contains(pos)).count());
+        }
+    });
+}
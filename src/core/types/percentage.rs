@@ -1,3 +1,5 @@
+use std::ops::{Sub, SubAssign};
+
 use super::Real;
 
 const LOWER_BOUND: Real = 0.0;
@@ -21,3 +23,27 @@ impl From<Real> for Percentage {
         }
     }
 }
+
+impl Percentage {
+    pub fn value(&self) -> Real {
+        self.value
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.value <= LOWER_BOUND
+    }
+}
+
+impl Sub<Real> for Percentage {
+    type Output = Self;
+
+    fn sub(self, rhs: Real) -> Self::Output {
+        (self.value - rhs).into()
+    }
+}
+
+impl SubAssign<Real> for Percentage {
+    fn sub_assign(&mut self, rhs: Real) {
+        *self = *self - rhs;
+    }
+}
@@ -1,3 +1,5 @@
+#![cfg_attr(all(test, feature = "bench"), feature(test))]
+
 use crate::core::systems::advance_time;
 
 use bevy::prelude::*;
@@ -22,11 +24,16 @@ mod world;
 #[cfg(test)]
 mod test;
 
+#[cfg(all(test, feature = "bench"))]
+mod bench;
+
 #[derive(Debug, Clone, Eq, PartialEq, Hash)]
 pub enum AppState {
     Paused,
     Running,
     Rendering,
+    /// The player spends the points they gained levelling up.
+    LevelUp,
 }
 
 const WIDTH: Int = 1280;
@@ -56,6 +63,7 @@ fn main() {
         .add_system_set(
             SystemSet::on_update(AppState::Running)
                 .with_system(advance_time)
+                .after(ActorSystems::Pace)
                 .before(ActorSystems::Action),
         )
         .add_system_set(
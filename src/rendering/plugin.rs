@@ -3,7 +3,7 @@ use bevy::prelude::*;
 use crate::{AppState, SystemLabels};
 
 use super::{
-    systems::{draw, load_char_tiles, pre_draw},
+    systems::{draw, draw_level_up, load_char_tiles, pre_draw},
     DrawEvent,
 };
 
@@ -22,6 +22,16 @@ impl Plugin for TileRendererPlugin {
                 SystemSet::on_update(AppState::Paused)
                     .label(SystemLabels::Rendering)
                     .with_system(draw),
+            )
+            .add_system_set(
+                SystemSet::on_update(AppState::LevelUp)
+                    .before(SystemLabels::Rendering)
+                    .with_system(draw_level_up),
+            )
+            .add_system_set(
+                SystemSet::on_update(AppState::LevelUp)
+                    .label(SystemLabels::Rendering)
+                    .with_system(draw),
             );
     }
 }
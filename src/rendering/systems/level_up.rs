@@ -0,0 +1,156 @@
+use bevy::prelude::*;
+
+use crate::{
+    actors::{Actor, Attribute, Experience, PerkTree, Player, Stat},
+    core::types::Int,
+    rendering::{constants::WORLD_VIEWPORT_DIMENSIONS, draw_event::CP437TileInfo, DrawEvent},
+    util::helpers::cp437,
+};
+
+/// Lines of text drawn onto an otherwise blank viewport.
+struct Page {
+    tiles: Vec<CP437TileInfo>,
+    line: usize,
+}
+
+impl Page {
+    fn new() -> Self {
+        let (columns, rows) = WORLD_VIEWPORT_DIMENSIONS;
+        Self {
+            tiles: (0..columns * rows)
+                .map(|_| CP437TileInfo {
+                    sprite_index: 0,
+                    fg: Color::BLACK,
+                    bg: Color::BLACK,
+                })
+                .collect(),
+            line: 1,
+        }
+    }
+
+    /// Writes `text` on the next line, cut off at the edge of the viewport.
+    fn write(&mut self, text: &str, color: Color) {
+        let (columns, rows) = WORLD_VIEWPORT_DIMENSIONS;
+        if self.line >= rows {
+            return;
+        }
+        // Tiles run column by column.
+        for (x, c) in text.chars().enumerate().take(columns - 2) {
+            self.tiles[(x + 1) * rows + self.line] = CP437TileInfo {
+                sprite_index: cp437(c),
+                fg: color,
+                bg: Color::BLACK,
+            };
+        }
+        self.line += 1;
+    }
+
+    fn skip(&mut self) {
+        self.line += 1;
+    }
+}
+
+fn attribute_name(attribute: Attribute) -> &'static str {
+    match attribute {
+        Attribute::Strength => "Strength",
+        Attribute::Agility => "Agility",
+        Attribute::Perception => "Perception",
+        Attribute::Endurance => "Endurance",
+        Attribute::Willpower => "Willpower",
+    }
+}
+
+fn stat_name(stat: Stat) -> &'static str {
+    match stat {
+        Stat::MaxHealth => "Health",
+        Stat::Speed => "Speed",
+        Stat::SightRange => "Sight",
+        Stat::CarryCapacity => "Carry",
+        Stat::MeleeDamage => "Melee",
+        Stat::RangedDamage => "Ranged",
+        Stat::Windup => "Windup",
+        Stat::Resolve => "Resolve",
+    }
+}
+
+const SHOWN_STATS: [Stat; 7] = [
+    Stat::MaxHealth,
+    Stat::Speed,
+    Stat::SightRange,
+    Stat::MeleeDamage,
+    Stat::RangedDamage,
+    Stat::Windup,
+    Stat::Resolve,
+];
+
+/// Draws the level up screen: the player's attributes and stats, with what modifiers
+/// add to each stat, and the choices they have left to make.
+pub fn draw_level_up(
+    perk_tree: Res<PerkTree>,
+    player: Query<(&Actor, &Experience), With<Player>>,
+    mut draw_event_writer: EventWriter<DrawEvent>,
+) {
+    let (actor, experience) = match player.iter().next() {
+        Some(player) => player,
+        None => return,
+    };
+    let active = |is_active: bool| if is_active { Color::WHITE } else { Color::GRAY };
+
+    let mut page = Page::new();
+    page.write(&format!("LEVEL {}", experience.level), Color::YELLOW);
+    page.write(
+        &format!("XP {}/{}", experience.points, experience.next_level_at()),
+        Color::GRAY,
+    );
+    page.skip();
+
+    let choosing_attributes = experience.attribute_points > 0;
+    page.write(
+        &format!("Attribute points: {}", experience.attribute_points),
+        active(choosing_attributes),
+    );
+    for (index, attribute) in Attribute::ALL.iter().enumerate() {
+        page.write(
+            &format!(
+                "{} {:<11}{:>3}",
+                index + 1,
+                attribute_name(*attribute),
+                actor.sheet.attribute(*attribute)
+            ),
+            active(choosing_attributes),
+        );
+    }
+    page.skip();
+
+    for stat in SHOWN_STATS {
+        let breakdown = actor.sheet.breakdown(stat);
+        let modified: Int = breakdown.steps.iter().map(|(_, change)| change).sum();
+        let text = match modified {
+            0 => format!("  {:<9}{:>4}", stat_name(breakdown.stat), breakdown.total),
+            _ => format!(
+                "  {:<9}{:>4} {:+} = {}",
+                stat_name(breakdown.stat),
+                breakdown.base,
+                modified,
+                breakdown.total
+            ),
+        };
+        page.write(&text, Color::GRAY);
+    }
+    page.skip();
+
+    let choosing_perks = !choosing_attributes && experience.perk_points > 0;
+    page.write(
+        &format!("Perk points: {}", experience.perk_points),
+        active(choosing_perks),
+    );
+    for (index, perk) in perk_tree.available(experience).take(9).enumerate() {
+        page.write(
+            &format!("{} {}", index + 1, perk.name),
+            active(choosing_perks),
+        );
+        page.write(&format!("  {}", perk.description), Color::GRAY);
+    }
+
+    draw_event_writer.send(DrawEvent { tiles: page.tiles });
+}
@@ -1,5 +1,8 @@
 mod init_tile_grid;
 pub use init_tile_grid::*;
 
+mod level_up;
+pub use level_up::*;
+
 mod tile_grid;
 pub use tile_grid::*;
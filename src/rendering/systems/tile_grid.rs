@@ -1,6 +1,9 @@
+use std::collections::HashMap;
+
 use bevy::prelude::*;
 
 use crate::{
+    ai::{Awareness, AwarenessState},
     core::{
         types::{GridPos, Int},
         MainPointOfView,
@@ -9,21 +12,26 @@ use crate::{
         constants::WORLD_VIEWPORT_DIMENSIONS, draw_event::CP437TileInfo, grid::Grid, CP437Tile,
         DrawEvent,
     },
-    util::helpers::{colors::greyscale, cp437},
-    world::{TileType, WorldMap},
+    util::helpers::{
+        colors::{greyscale, shade},
+        cp437,
+    },
+    world::{Renderable, WorldMap},
 };
 
 pub fn pre_draw(
     world_map: Res<WorldMap>,
+    renderables: Query<&Renderable>,
+    awareness_query: Query<(&GridPos, &Awareness)>,
     grid_query: Query<&Children, With<Grid>>,
     pov_query: Query<&GridPos, With<MainPointOfView>>,
     mut draw_event_writer: EventWriter<DrawEvent>,
 ) {
     let camera_pos = pov_query.single();
-
-    let offset_area = world_map.get_area_from_pos(&camera_pos.0).unwrap();
-    let offset = &offset_area.0;
-    let area = &offset_area.1;
+    let (min, max) = match world_map.bounds() {
+        Some(bounds) => bounds,
+        None => return,
+    };
 
     let mut draw_map: Vec<CP437TileInfo> = vec![];
     let (columns, rows) = WORLD_VIEWPORT_DIMENSIONS;
@@ -31,37 +39,79 @@ pub fn pre_draw(
 
     let viewport_tiles = grid_query.single();
 
-    let (min_x, max_x) = (offset.x, offset.x + area.width - 1);
-    let (min_y, max_y) = (offset.y, offset.y + area.height - 1);
+    // Monsters that noticed something show it on the tile above them.
+    let indicators: HashMap<IVec2, AwarenessState> = awareness_query
+        .iter()
+        .filter(|(_, awareness)| awareness.state() != AwarenessState::Unaware)
+        .map(|(pos, awareness)| (pos.0 - IVec2::Y, awareness.state()))
+        .collect();
 
+    // Keeps the viewport inside the world, centering it when the world is the smaller one.
+    let clamp_axis = |camera: Int, min: Int, max: Int, size: Int| {
+        let start = camera - size / 2;
+        if max - min + 1 <= size {
+            min - (size - (max - min + 1)) / 2
+        } else {
+            start.clamp(min, max - size + 1)
+        }
+    };
     let top_left = IVec2::new(
-        (camera_pos.0.x - (columns / 2) as Int).clamp(min_x, max_x - columns as Int + 1),
-        (camera_pos.0.y - (rows / 2) as Int).clamp(min_y, max_y - rows as Int + 1),
+        clamp_axis(camera_pos.0.x, min.x, max.x, columns as Int),
+        clamp_axis(camera_pos.0.y, min.y, max.y, rows as Int),
     );
 
     (0..viewport_tiles.len()).for_each(|index| {
         let y = (index % columns) as Int;
         let x = (index / columns) as Int;
-        let pos = IVec2::new(
-            (top_left.x + x).clamp(min_x, max_x),
-            (top_left.y + y).clamp(min_y, max_y),
-        );
+        let pos = IVec2::new(top_left.x + x, top_left.y + y);
 
-        let tile = area.tile_at(&pos).unwrap();
-        let mut sprite_index = match tile.which() {
-            TileType::Wall => 35_u32,
-            TileType::Floor => 46_u32,
+        let tile = match world_map.tile_at(&pos) {
+            Some(tile) => tile,
+            None => {
+                draw_map.push(CP437TileInfo {
+                    sprite_index: 0,
+                    fg: Color::BLACK,
+                    bg: Color::BLACK,
+                });
+                return;
+            }
         };
-
-        let mut fg = Color::ORANGE;
-        let mut bg = Color::SEA_GREEN;
+        let definition = tile.which().definition();
+        let mut sprite_index = cp437(definition.glyph);
+        let mut fg = definition.fg();
+        let mut bg = definition.bg();
 
         if tile.is_visible() {
-            if let Some(renderable) = area.renderables.get(&pos) {
+            // The latest arrival on a tile is drawn on top.
+            let renderable = world_map
+                .occupants_at(&pos)
+                .iter()
+                .rev()
+                .find_map(|occupant| renderables.get(occupant.entity).ok());
+            if let Some(renderable) = renderable {
                 sprite_index = cp437(renderable.glyph);
                 fg = renderable.fg;
                 bg = renderable.bg;
             }
+            let light = world_map.light_at(&pos);
+            fg = shade(&fg, &light.color, light.intensity);
+            bg = shade(&bg, &light.color, light.intensity);
+
+            // Drawn unshaded so it stays readable in the dark.
+            let is_below_visible = world_map
+                .tile_at(&(pos + IVec2::Y))
+                .map_or(false, |below| below.is_visible());
+            match indicators.get(&pos) {
+                Some(state) if renderable.is_none() && is_below_visible => {
+                    let (glyph, color) = match state {
+                        AwarenessState::Alerted => ('!', Color::RED),
+                        _ => ('?', Color::YELLOW),
+                    };
+                    sprite_index = cp437(glyph);
+                    fg = color;
+                }
+                _ => (),
+            }
         } else if !tile.is_revealed() {
             fg = Color::BLACK;
             bg = Color::BLACK;
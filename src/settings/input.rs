@@ -23,6 +23,13 @@ impl Default for PlayerSettings {
                 (KeyCode::Period, Action::Wait),
                 (KeyCode::Return, Action::InitiateAttack),
                 (KeyCode::J, Action::InitiateAttack),
+                (KeyCode::G, Action::InitiateSweep),
+                (KeyCode::Space, Action::UseStairs),
+                (KeyCode::O, Action::Open),
+                (KeyCode::K, Action::Close),
+                (KeyCode::F, Action::Shove),
+                (KeyCode::T, Action::ToggleLight),
+                (KeyCode::R, Action::Fire),
             ]),
         }
     }
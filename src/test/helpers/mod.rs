@@ -3,3 +3,5 @@ pub mod activity;
 pub mod visibility;
 
 pub mod rotation;
+
+pub mod world;
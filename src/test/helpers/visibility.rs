@@ -40,10 +40,10 @@ pub fn from_ascii_layout(ascii_map: &str) -> (IVec2, AreaGrid) {
         assert_eq!(width, row.trim_start().len() as Int);
         for (index, c) in row.trim_start().char_indices() {
             match c {
-                '.' => tiles.push(TileType::Floor),
-                '#' => tiles.push(TileType::Wall),
+                '.' => tiles.push(TileType::FLOOR),
+                '#' => tiles.push(TileType::WALL),
                 '@' => {
-                    tiles.push(TileType::Floor);
+                    tiles.push(TileType::FLOOR);
                     // origin.x = (index as Int) - (width / 2);
                     origin.x = index as Int;
                     origin.y = y as Int;
@@ -0,0 +1,43 @@
+use bevy::prelude::*;
+
+use crate::{
+    core::{types::Increment, TimeIncrementEvent},
+    world::{AreaGrid, WorldMap},
+};
+
+use super::visibility::from_ascii_layout;
+
+/// A map made of `area` alone, placed at the origin.
+pub fn world_map_with_area(area: AreaGrid) -> WorldMap {
+    let mut world_map = WorldMap::default();
+    world_map.insert_offset(&IVec2::ZERO, area);
+    world_map
+}
+
+pub fn world_with_area(area: AreaGrid) -> World {
+    let mut world = World::new();
+    world.insert_resource(world_map_with_area(area));
+    world
+}
+
+/// A world mapped from an ascii layout, along with the position of its `@`.
+pub fn world_from_ascii_layout(layout: &str) -> (World, IVec2) {
+    let (origin, area) = from_ascii_layout(layout);
+    (world_with_area(area), origin)
+}
+
+/// Sends a time increment and runs `stage` to let it pass. Reusing the same stage
+/// keeps its event readers from seeing old increments again.
+pub fn pass_time(world: &mut World, stage: &mut SystemStage, delta_time: Increment) {
+    world
+        .get_resource_mut::<Events<TimeIncrementEvent>>()
+        .unwrap()
+        .send(TimeIncrementEvent { delta_time });
+    stage.run(world);
+}
+
+/// Every event of type `E` still held by the world.
+pub fn sent_events<E: Clone + Send + Sync + 'static>(world: &World) -> Vec<E> {
+    let events = world.get_resource::<Events<E>>().unwrap();
+    events.get_reader().iter(events).cloned().collect()
+}
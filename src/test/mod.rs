@@ -1,2 +1,2 @@
 pub mod helpers;
-pub use helpers::{activity, rotation, visibility};
+pub use helpers::{activity, rotation, visibility, world};
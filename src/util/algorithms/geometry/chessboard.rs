@@ -1,12 +1,34 @@
 use bevy::math::IVec2;
 
-use crate::core::types::Int;
+use crate::core::{
+    constants::PI,
+    types::{Cardinal, Int, Real},
+};
 
 pub fn chessboard_distance(start: &IVec2, end: &IVec2) -> Int {
     let delta = *end - *start;
     std::cmp::max(delta.x.abs(), delta.y.abs())
 }
 
+pub fn chessboard_neighbours(pos: &IVec2) -> impl Iterator<Item = IVec2> {
+    let pos = *pos;
+    (-1..=1)
+        .flat_map(|y| (-1..=1).map(move |x| IVec2::new(x, y)))
+        .filter(|delta| *delta != IVec2::ZERO)
+        .map(move |delta| pos + delta)
+}
+
+/// Closest of the eight cardinals pointing from `start` to `end`, if they differ.
+pub fn cardinal_towards(start: &IVec2, end: &IVec2) -> Option<Cardinal> {
+    let delta = *end - *start;
+    if delta == IVec2::ZERO {
+        return None;
+    }
+    let angle = (delta.x as Real).atan2(-delta.y as Real);
+    let octant = (angle / (PI / 4.0)).round() as Int;
+    Some(octant.rem_euclid(8).into())
+}
+
 pub fn chessboard_rotate_and_place(
     origin: &IVec2,
     positions: &[IVec2],
@@ -59,7 +81,7 @@ mod tests {
         test,
     };
 
-    use super::chessboard_rotate_and_place;
+    use super::{cardinal_towards, chessboard_rotate_and_place};
 
     #[test]
     fn chessboard_rotations() {
@@ -81,4 +103,22 @@ mod tests {
                 });
         });
     }
+
+    #[test]
+    fn cardinals_towards_targets() {
+        let cases = [
+            (IVec2::new(0, -5), Cardinal::North),
+            (IVec2::new(3, -3), Cardinal::NorthEast),
+            (IVec2::new(4, 1), Cardinal::East),
+            (IVec2::new(2, 2), Cardinal::SouthEast),
+            (IVec2::new(0, 1), Cardinal::South),
+            (IVec2::new(-1, 1), Cardinal::SouthWest),
+            (IVec2::new(-7, 0), Cardinal::West),
+            (IVec2::new(-2, -2), Cardinal::NorthWest),
+        ];
+        for (target, expected) in cases {
+            assert_eq!(Some(expected), cardinal_towards(&IVec2::ZERO, &target));
+        }
+        assert_eq!(None, cardinal_towards(&IVec2::ONE, &IVec2::ONE));
+    }
 }
@@ -0,0 +1,91 @@
+use bevy::math::{IVec2, Vec2};
+
+use crate::core::types::{Predicate, Real};
+
+/// Points within the target tile that rays are aimed at when estimating cover.
+const COVER_SAMPLES: [(Real, Real); 4] = [(-0.3, -0.3), (0.3, -0.3), (-0.3, 0.3), (0.3, 0.3)];
+
+/// Share of the target tile hidden from the center of `start` by blocking tiles in
+/// between, from 0 in the open to 1 when completely out of sight.
+pub fn cover(start: &IVec2, target: &IVec2, is_blocking: &Predicate<IVec2>) -> Real {
+    if start == target {
+        return 0.0;
+    }
+    let blocked = COVER_SAMPLES
+        .iter()
+        .filter(|(x, y)| {
+            ray(start.as_vec2(), target.as_vec2() + Vec2::new(*x, *y))
+                .iter()
+                .any(|pos| pos != target && is_blocking(pos))
+        })
+        .count();
+    blocked as Real / COVER_SAMPLES.len() as Real
+}
+
+/// Tiles a ray passes through after leaving its first one, with tile centers on
+/// integer coordinates.
+fn ray(from: Vec2, to: Vec2) -> Vec<IVec2> {
+    let end = to.round().as_ivec2();
+    let mut tile = from.round().as_ivec2();
+    let delta = to - from;
+    let step = delta.signum().as_ivec2();
+    // Share of the ray it takes to cross a whole tile along each axis.
+    let t_delta = Vec2::new(1.0 / delta.x.abs(), 1.0 / delta.y.abs());
+    let boundary = tile.as_vec2() + step.as_vec2() * 0.5;
+    let to_boundary = |boundary: Real, from: Real, delta: Real| {
+        if delta == 0.0 {
+            Real::INFINITY
+        } else {
+            (boundary - from) / delta
+        }
+    };
+    let mut t_max = Vec2::new(
+        to_boundary(boundary.x, from.x, delta.x),
+        to_boundary(boundary.y, from.y, delta.y),
+    );
+
+    let mut tiles = vec![];
+    while tile != end {
+        if t_max.x < t_max.y {
+            tile.x += step.x;
+            t_max.x += t_delta.x;
+        } else {
+            tile.y += step.y;
+            t_max.y += t_delta.y;
+        }
+        tiles.push(tile);
+    }
+    tiles
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::math::IVec2;
+
+    use crate::test::helpers::visibility::from_ascii_layout;
+
+    use super::cover;
+
+    fn cover_in(layout: &str, target: IVec2) -> f32 {
+        let (origin, map) = from_ascii_layout(layout);
+        cover(&origin, &target, &|pos| map.is_opaque(pos))
+    }
+
+    #[test]
+    fn walls_in_between_give_cover() {
+        let open = "@......
+                    .......";
+        assert_eq!(0.0, cover_in(open, IVec2::new(6, 1)));
+
+        let behind_wall = "@......
+                           .....#.";
+        assert_eq!(1.0, cover_in(behind_wall, IVec2::new(6, 1)));
+
+        let past_a_corner = "@........
+                             .........
+                             .........
+                             .......#.";
+        let partial = cover_in(past_a_corner, IVec2::new(8, 3));
+        assert!(0.0 < partial && partial < 1.0, "{}", partial);
+    }
+}
@@ -0,0 +1,176 @@
+use bevy::math::IVec2;
+
+use crate::core::types::{Index, Int, Predicate};
+
+/// Tiles on the line from `start` to `end`, both included. Ties go towards the end,
+/// so the line drawn back from `end` can differ.
+// Line of sight and projectiles use `symmetric_line`; this one is public API for
+// whatever wants the classic line instead.
+#[allow(dead_code)]
+pub fn bresenham(start: &IVec2, end: &IVec2) -> Vec<IVec2> {
+    let delta = (*end - *start).abs();
+    let step = (*end - *start).signum();
+    let mut error = delta.x - delta.y;
+    let mut pos = *start;
+    let mut line = Vec::with_capacity((delta.x.max(delta.y) + 1) as Index);
+    loop {
+        line.push(pos);
+        if pos == *end {
+            return line;
+        }
+        let doubled = 2 * error;
+        if doubled > -delta.y {
+            error -= delta.y;
+            pos.x += step.x;
+        }
+        if doubled < delta.x {
+            error += delta.x;
+            pos.y += step.y;
+        }
+    }
+}
+
+/// Tiles whose centers lie closest to the straight line between the centers of
+/// `start` and `end`, both included. Ties are broken the same whichever end the line
+/// starts from, so the line from `end` holds the same tiles in reverse.
+pub fn symmetric_line(start: &IVec2, end: &IVec2) -> Vec<IVec2> {
+    let delta = *end - *start;
+    let steps = delta.x.abs().max(delta.y.abs());
+    if steps == 0 {
+        return vec![*start];
+    }
+    // Nearest integer to `from + offset * step / steps`, ties rounding up.
+    let along = |from: Int, offset: Int, step: Int| {
+        let num = from * steps + offset * step;
+        (2 * num + steps).div_euclid(2 * steps)
+    };
+    (0..=steps)
+        .map(|step| IVec2::new(along(start.x, delta.x, step), along(start.y, delta.y, step)))
+        .collect()
+}
+
+/// Whether nothing blocks the symmetric line between two tiles. The tiles at either
+/// end may block themselves. A tile with a clear line is always in the field of
+/// view `symmetric_shadowcasting` gives, though not every tile in view has one.
+pub fn has_line_of_sight(start: &IVec2, end: &IVec2, is_blocking: &Predicate<IVec2>) -> bool {
+    let line = symmetric_line(start, end);
+    let before_end = &line[..line.len() - 1];
+    first_blocking(before_end, is_blocking).is_none()
+}
+
+/// Tiles after the start of `line` up to and including the first that blocks, or
+/// the whole rest of the line when nothing does.
+pub fn tiles_crossed<'a>(line: &'a [IVec2], is_blocking: &Predicate<IVec2>) -> &'a [IVec2] {
+    let rest = line.get(1..).unwrap_or_default();
+    match rest.iter().position(|pos| is_blocking(pos)) {
+        Some(index) => &rest[..=index],
+        None => rest,
+    }
+}
+
+/// First tile after the start of `line` that blocks.
+pub fn first_blocking(line: &[IVec2], is_blocking: &Predicate<IVec2>) -> Option<IVec2> {
+    line.iter().skip(1).copied().find(|pos| is_blocking(pos))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use bevy::math::IVec2;
+    use rltk::RandomNumberGenerator;
+
+    use crate::{
+        test::helpers::visibility::from_ascii_layout,
+        util::algorithms::{geometry::chessboard_distance, symmetric_shadowcasting},
+    };
+
+    use super::{bresenham, first_blocking, has_line_of_sight, symmetric_line, tiles_crossed};
+
+    fn assert_connected(line: &[IVec2], start: IVec2, end: IVec2) {
+        assert_eq!(Some(&start), line.first());
+        assert_eq!(Some(&end), line.last());
+        assert_eq!(chessboard_distance(&start, &end) as usize + 1, line.len());
+        for step in line.windows(2) {
+            assert_eq!(1, chessboard_distance(&step[0], &step[1]));
+        }
+    }
+
+    #[test]
+    fn lines_step_one_tile_at_a_time() {
+        let start = IVec2::new(2, -3);
+        for end in [
+            IVec2::new(9, 1),
+            IVec2::new(-4, -5),
+            IVec2::new(2, 6),
+            IVec2::new(-1, 0),
+            start,
+        ] {
+            assert_connected(&bresenham(&start, &end), start, end);
+            assert_connected(&symmetric_line(&start, &end), start, end);
+        }
+    }
+
+    #[test]
+    fn symmetric_lines_are_the_same_both_ways() {
+        let start = IVec2::new(0, 0);
+        for end in [IVec2::new(4, 2), IVec2::new(-3, 1), IVec2::new(5, -5)] {
+            let mut back = symmetric_line(&end, &start);
+            back.reverse();
+            assert_eq!(symmetric_line(&start, &end), back);
+        }
+    }
+
+    #[test]
+    fn projectiles_stop_at_the_first_wall() {
+        let (origin, map) = from_ascii_layout(
+            "########
+             #@..#..#
+             ########",
+        );
+        let is_blocking = |pos: &IVec2| map.is_opaque(pos);
+        let line = symmetric_line(&origin, &IVec2::new(6, 1));
+
+        assert_eq!(Some(IVec2::new(4, 1)), first_blocking(&line, &is_blocking));
+        assert_eq!(
+            vec![IVec2::new(2, 1), IVec2::new(3, 1), IVec2::new(4, 1)],
+            tiles_crossed(&line, &is_blocking)
+        );
+        assert!(has_line_of_sight(&origin, &IVec2::new(4, 1), &is_blocking));
+        assert!(!has_line_of_sight(&origin, &IVec2::new(5, 1), &is_blocking));
+    }
+
+    #[test]
+    fn clear_lines_are_in_the_field_of_view() {
+        let mut rng = RandomNumberGenerator::seeded(44);
+        for _ in 0..50 {
+            let size = rng.range(5, 25);
+            let density = rng.range(0, 30);
+            let mut walls: HashSet<IVec2> = HashSet::new();
+            for y in 0..size {
+                for x in 0..size {
+                    if rng.range(0, 100) < density {
+                        walls.insert(IVec2::new(x, y));
+                    }
+                }
+            }
+            let origin = IVec2::new(rng.range(0, size), rng.range(0, size));
+            walls.remove(&origin);
+            let is_blocking = |pos: &IVec2| {
+                walls.contains(pos) || pos.min_element() < 0 || pos.max_element() >= size
+            };
+
+            let in_view: HashSet<IVec2> = symmetric_shadowcasting(&origin, &|_| true, &is_blocking)
+                .into_iter()
+                .collect();
+            for y in 0..size {
+                for x in 0..size {
+                    let target = IVec2::new(x, y);
+                    if has_line_of_sight(&origin, &target, &is_blocking) {
+                        assert!(in_view.contains(&target), "{:?} to {:?}", origin, target);
+                    }
+                }
+            }
+        }
+    }
+}
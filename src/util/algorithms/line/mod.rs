@@ -0,0 +1,5 @@
+mod cover;
+pub use cover::*;
+
+mod lines;
+pub use lines::*;
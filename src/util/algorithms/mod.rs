@@ -1,4 +1,11 @@
 pub mod geometry;
 
+pub mod line;
+
+pub mod pathfinding;
+
 mod visibility;
-pub use visibility::{field_of_view, symmetric_shadowcasting, QuadrantRow};
+pub use visibility::{field_of_view, shadowcast, symmetric_shadowcasting, QuadrantRow};
+
+#[cfg(all(test, feature = "bench"))]
+pub use visibility::reference;
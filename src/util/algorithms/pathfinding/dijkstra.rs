@@ -0,0 +1,76 @@
+use std::{cmp::Reverse, collections::BinaryHeap, collections::HashMap};
+
+use bevy::math::IVec2;
+
+use crate::{core::types::Int, util::algorithms::geometry::chessboard_neighbours};
+
+/// Cost of stepping onto a position, or `None` if it cannot be entered at all.
+pub type StepCost<'a> = dyn Fn(&IVec2) -> Option<Int> + 'a;
+
+/// Computes the cheapest cost of reaching every position from the nearest origin,
+/// moving in the eight chessboard directions. Positions costing more than `max_cost`
+/// are left out.
+pub fn dijkstra_map(origins: &[IVec2], max_cost: Int, step_cost: &StepCost) -> HashMap<IVec2, Int> {
+    let mut costs: HashMap<IVec2, Int> = HashMap::new();
+    let mut frontier: BinaryHeap<Reverse<(Int, Int, Int)>> = BinaryHeap::new();
+
+    for origin in origins {
+        costs.insert(*origin, 0);
+        frontier.push(Reverse((0, origin.x, origin.y)));
+    }
+
+    while let Some(Reverse((cost, x, y))) = frontier.pop() {
+        let pos = IVec2::new(x, y);
+        if costs.get(&pos).map_or(false, |best| *best < cost) {
+            continue;
+        }
+
+        for neighbour in chessboard_neighbours(&pos) {
+            let next_cost = match step_cost(&neighbour) {
+                Some(step) => cost + step,
+                None => continue,
+            };
+            if next_cost > max_cost {
+                continue;
+            }
+            if costs.get(&neighbour).map_or(true, |best| next_cost < *best) {
+                costs.insert(neighbour, next_cost);
+                frontier.push(Reverse((next_cost, neighbour.x, neighbour.y)));
+            }
+        }
+    }
+
+    costs
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::math::IVec2;
+
+    use super::dijkstra_map;
+
+    #[test]
+    fn open_ground_is_chessboard_distance() {
+        let costs = dijkstra_map(&[IVec2::ZERO], 3, &|_| Some(1));
+
+        assert_eq!(Some(&0), costs.get(&IVec2::ZERO));
+        assert_eq!(Some(&1), costs.get(&IVec2::new(1, 1)));
+        assert_eq!(Some(&3), costs.get(&IVec2::new(-3, 2)));
+        assert!(costs.get(&IVec2::new(4, 0)).is_none());
+    }
+
+    #[test]
+    fn impassable_positions_are_skipped() {
+        let wall = |pos: &IVec2| pos.x == 1 && pos.y.abs() <= 1;
+        let costs = dijkstra_map(&[IVec2::ZERO], 10, &|pos| {
+            if wall(pos) {
+                None
+            } else {
+                Some(1)
+            }
+        });
+
+        assert!(costs.get(&IVec2::new(1, 0)).is_none());
+        assert_eq!(Some(&4), costs.get(&IVec2::new(2, 0)));
+    }
+}
@@ -0,0 +1,2 @@
+mod dijkstra;
+pub use dijkstra::*;
@@ -1,4 +1,5 @@
 use bevy::math::{IVec2, Vec2};
+use serde::Deserialize;
 
 use crate::{
     core::types::{Cardinal, Facing, Int, Real},
@@ -8,6 +9,8 @@ use crate::{
     },
 };
 
+/// Shape of what an actor can see, relative to its position and facing.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
 pub enum FOV {
     Infinite,
     Omnidirectional(Int),
@@ -26,6 +29,39 @@ impl FOV {
             FOV::Pattern(pattern) => is_in_pattern(pattern, position, cardinal),
         }
     }
+
+    /// Furthest chessboard distance the shape reaches.
+    pub fn range(&self) -> Int {
+        match self {
+            FOV::Infinite => Int::MAX,
+            FOV::Omnidirectional(range) | FOV::Cone(range, _) | FOV::Quadratic(range, _, _) => {
+                *range
+            }
+            FOV::Pattern(pattern) => pattern
+                .iter()
+                .map(|pos| chessboard_distance(&IVec2::ZERO, pos))
+                .max()
+                .unwrap_or(0),
+        }
+    }
+
+    /// The same shape reaching `extra` tiles further, or closer when negative.
+    /// Unbounded and hand-drawn shapes stay as they are.
+    pub fn extended(&self, extra: Int) -> FOV {
+        let extend = |range: &Int| (range + extra).max(0);
+        match self {
+            FOV::Omnidirectional(range) => FOV::Omnidirectional(extend(range)),
+            FOV::Cone(range, angle) => FOV::Cone(extend(range), *angle),
+            FOV::Quadratic(range, a, b) => FOV::Quadratic(extend(range), *a, *b),
+            fov => fov.clone(),
+        }
+    }
+}
+
+impl Default for FOV {
+    fn default() -> Self {
+        FOV::Quadratic(15, 0.35, -1.5)
+    }
 }
 
 fn is_in_range(position: &IVec2, range: Int) -> bool {
@@ -1,12 +1,14 @@
 pub mod field_of_view;
 
+#[cfg(test)]
+pub mod reference;
+
 use bevy::math::IVec2;
-use fraction::Fraction;
 use std::collections::HashSet;
 
 use crate::{
     core::types::{Cardinal, Facing, Int, Predicate},
-    util::{helpers::GridRotator, math::RealToInt},
+    util::helpers::GridRotator,
 };
 
 type MarkPosition<'a> = dyn FnMut(&IVec2) -> bool + 'a;
@@ -17,8 +19,26 @@ pub fn symmetric_shadowcasting(
     is_blocking: &Predicate<IVec2>,
 ) -> Vec<IVec2> {
     let mut visible_positions: HashSet<IVec2> = HashSet::new();
+    shadowcast(
+        origin,
+        &mut |pos| visible_positions.insert(*pos),
+        is_visible,
+        is_blocking,
+    );
+    visible_positions.into_iter().collect()
+}
+
+/// Same as `symmetric_shadowcasting`, handing each visible position to `mark` as it
+/// is found instead of collecting them. Positions on the quadrant edges can be
+/// marked more than once.
+pub fn shadowcast(
+    origin: &IVec2,
+    mark: &mut MarkPosition,
+    is_visible: &Predicate<IVec2>,
+    is_blocking: &Predicate<IVec2>,
+) {
     if is_visible(&IVec2::ZERO) {
-        visible_positions.insert(*origin);
+        mark(origin);
     }
 
     let cardinals = [
@@ -29,212 +49,163 @@ pub fn symmetric_shadowcasting(
     ];
 
     for cardinal in cardinals {
-        scan(
-            origin,
-            cardinal,
-            &mut |pos| visible_positions.insert(*pos),
-            is_visible,
-            is_blocking,
-        )
+        Quadrant::new(*origin, cardinal).scan(mark, is_visible, is_blocking);
     }
-
-    visible_positions.into_iter().collect()
 }
 
-fn scan(
-    origin: &IVec2,
-    cardinal: Cardinal,
-    mark: &mut MarkPosition,
-    is_visible: &Predicate<IVec2>,
-    is_blocking: &Predicate<IVec2>,
-) {
-    QuadrantRow::new(*origin, cardinal).scan(mark, is_visible, is_blocking);
-}
+/// One of the four quarters scanned from the origin, mapping row depth and
+/// column to world positions.
 #[derive(Debug, Clone, Copy)]
 struct Quadrant {
-    facing: Facing,
     origin: IVec2,
+    depth_axis: IVec2,
+    column_axis: IVec2,
 }
 
 impl Quadrant {
-    pub fn new(facing: Facing, origin: IVec2) -> Self {
-        Self { facing, origin }
-    }
-
-    pub fn transform(&self, point: IVec2) -> IVec2 {
-        self.origin + self.facing.rot_i(&point)
-    }
-}
-
-#[derive(Debug, Clone, Copy)]
-struct QuadrantTile {
-    row_depth: u16,
-    column: u32,
-    position: IVec2,
-}
-
-impl From<QuadrantTile> for Fraction {
-    fn from(tile: QuadrantTile) -> Self {
-        let row_depth = tile.row_depth;
-        let column = tile.column as i16;
-        let num = 2i16 * column - 1i16;
-        if 0 < num {
-            Fraction::new(num as u16, 2u16 * row_depth)
-        } else {
-            Fraction::new_neg(num.abs() as u16, 2u16 * row_depth)
+    fn new(origin: IVec2, cardinal: Cardinal) -> Self {
+        let facing: Facing = cardinal.into();
+        Self {
+            origin,
+            depth_axis: facing.rot_i(&IVec2::X),
+            column_axis: facing.rot_i(&IVec2::Y),
         }
     }
-}
-
-#[derive(Debug, Clone, Copy)]
-pub struct QuadrantRow {
-    quadrant: Quadrant,
-    depth: u16,
-    start_slope: Fraction,
-    end_slope: Fraction,
-}
 
-impl QuadrantRow {
-    pub fn new(origin: IVec2, cardinal: Cardinal) -> Self {
-        Self {
-            quadrant: Quadrant::new(cardinal.into(), origin),
-            depth: 1,
-            start_slope: Fraction::new_neg(1u16, 1u32),
-            end_slope: Fraction::new(1u16, 1u32),
-        }
+    fn transform(&self, depth: Int, column: Int) -> IVec2 {
+        self.origin + self.depth_axis * depth + self.column_axis * column
     }
 
-    pub fn scan(
-        &mut self,
-        mark_visible: &mut MarkPosition,
+    /// Scans rows outward from the origin. Rows still to scan wait on a stack
+    /// rather than in recursive calls.
+    fn scan(
+        &self,
+        mark: &mut MarkPosition,
         is_visible: &Predicate<IVec2>,
         is_blocking: &Predicate<IVec2>,
     ) {
-        let mut previous = IVec2::ZERO;
-        for tile in self.tiles(is_visible) {
-            if previous != IVec2::ZERO {
-                self.try_update_slope(tile, previous, is_blocking);
-            }
-
-            if self.check_next_row(tile.position, previous, is_blocking) {
-                self.next_from(tile.into())
-                    .scan(mark_visible, is_visible, is_blocking);
+        let mut rows = vec![QuadrantRow::first()];
+        while let Some(mut row) = rows.pop() {
+            let mut previous_blocking: Option<bool> = None;
+            for column in row.min_column()..=row.max_column() {
+                let position = self.transform(row.depth, column);
+                if !is_visible(&(position - self.origin)) {
+                    continue;
+                }
+
+                let blocking = is_blocking(&position);
+                match previous_blocking {
+                    Some(true) if !blocking => row.start = Slope::of_tile(row.depth, column),
+                    Some(false) if blocking => {
+                        rows.push(row.next_until(Slope::of_tile(row.depth, column)))
+                    }
+                    _ => (),
+                }
+
+                if blocking || row.is_symmetric(column) {
+                    mark(&position);
+                }
+                previous_blocking = Some(blocking);
             }
-
-            if is_blocking(&tile.position) || self.is_symmetric(tile.column as Int) {
-                mark_visible(&tile.position);
+            if previous_blocking == Some(false) {
+                rows.push(row.next());
             }
-
-            previous = tile.position;
-        }
-        if previous != IVec2::ZERO && !is_blocking(&previous) {
-            self.next().scan(mark_visible, is_visible, is_blocking);
         }
     }
+}
 
-    fn origin(&self) -> IVec2 {
-        self.quadrant.origin
-    }
+/// Slope from the origin as a fraction, `num / den` with `den` always positive.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Slope {
+    num: Int,
+    den: Int,
+}
 
-    fn next_from(&self, slope: Fraction) -> QuadrantRow {
-        let mut next = self.next();
-        next.end_slope = slope;
-        next
+impl Slope {
+    const fn new(num: Int, den: Int) -> Self {
+        Self { num, den }
     }
 
-    fn next(&self) -> QuadrantRow {
-        let mut next = self.clone();
-        next.depth += 1;
-        next
+    /// Slope through the edge between a tile and the one before it in its row.
+    fn of_tile(depth: Int, column: Int) -> Self {
+        Self::new(2 * column - 1, 2 * depth)
     }
+}
 
-    fn is_symmetric(&self, column: Int) -> bool {
-        let depth_fraction = Fraction::new(self.depth, 1u32);
+/// `num / den` rounded down.
+fn div_floor(num: Int, den: Int) -> Int {
+    num.div_euclid(den)
+}
 
-        let start_slope = depth_fraction * self.start_slope;
-        let start_slope_val = start_slope.int();
+/// `num / den` rounded up.
+fn div_ceil(num: Int, den: Int) -> Int {
+    -(-num).div_euclid(den)
+}
+
+/// `num / den` rounded to the nearest integer, halves away from zero.
+fn div_round(num: Int, den: Int) -> Int {
+    num.signum() * ((2 * num.abs() + den) / (2 * den))
+}
 
-        let end_slope = depth_fraction * self.end_slope;
-        let end_slope_val = end_slope.int();
+#[derive(Debug, Clone, Copy)]
+pub struct QuadrantRow {
+    depth: Int,
+    start: Slope,
+    end: Slope,
+}
 
-        column + 1 >= start_slope_val && column - 1 <= end_slope_val
+impl QuadrantRow {
+    fn first() -> Self {
+        Self {
+            depth: 1,
+            start: Slope::new(-1, 1),
+            end: Slope::new(1, 1),
+        }
     }
 
-    fn try_update_slope(
-        &mut self,
-        tile: QuadrantTile,
-        previous_position: IVec2,
-        is_blocking: &Predicate<IVec2>,
-    ) {
-        if is_blocking(&previous_position) && !is_blocking(&tile.position) {
-            let slope: Fraction = tile.into();
-            self.start_slope = slope;
+    fn next(&self) -> Self {
+        Self {
+            depth: self.depth + 1,
+            ..*self
         }
     }
 
-    fn check_next_row(
-        &self,
-        position: IVec2,
-        previous_position: IVec2,
-        is_blocking: &Predicate<IVec2>,
-    ) -> bool {
-        !is_blocking(&previous_position) && is_blocking(&position)
+    fn next_until(&self, end: Slope) -> Self {
+        Self { end, ..self.next() }
     }
 
-    fn tiles(&self, is_visible: &Predicate<IVec2>) -> Vec<QuadrantTile> {
-        let min_col = self
-            .round_ties_up(Fraction::new(self.depth, 1u32))
-            .round()
-            .int();
-        let max_col = self
-            .round_ties_down(Fraction::new(self.depth, 1u32))
-            .round()
-            .int();
-        let mut tiles: Vec<QuadrantTile> = Vec::new();
-        for column in min_col..=max_col {
-            let local_quadrant_position = IVec2::new(self.depth as Int, column);
-            let position = self.quadrant.transform(local_quadrant_position);
-            let delta = IVec2::new(position.x - self.origin().x, position.y - self.origin().y);
-            if is_visible(&delta) {
-                tiles.push(QuadrantTile {
-                    row_depth: self.depth,
-                    column: column as u32,
-                    position,
-                });
-            }
-        }
-        tiles
+    /// First column of the row, rounding ties up.
+    fn min_column(&self) -> Int {
+        div_floor(
+            2 * self.depth * self.start.num + self.start.den,
+            2 * self.start.den,
+        )
     }
 
-    fn round_ties_up(&self, n: Fraction) -> Fraction {
-        let sloped = self.start_slope * n;
-        let sum = sloped + Fraction::new(1u16, 2u32);
-        if sum.is_sign_negative() {
-            sum.ceil()
-        } else {
-            sum.floor()
-        }
+    /// Last column of the row, rounding ties down.
+    fn max_column(&self) -> Int {
+        div_ceil(
+            2 * self.depth * self.end.num - self.end.den,
+            2 * self.end.den,
+        )
     }
 
-    fn round_ties_down(&self, n: Fraction) -> Fraction {
-        let sloped = n * self.end_slope;
-        let sum = sloped - Fraction::new(1u16, 2u32);
-        if sum.is_sign_negative() {
-            sum.floor()
-        } else {
-            sum.ceil()
-        }
+    fn is_symmetric(&self, column: Int) -> bool {
+        column + 1 >= div_round(self.depth * self.start.num, self.start.den)
+            && column - 1 <= div_round(self.depth * self.end.num, self.end.den)
     }
 }
 
 #[cfg(test)]
 mod tests {
 
-    use std::cmp::Ordering;
+    use std::{cmp::Ordering, collections::HashSet};
 
     use bevy::math::IVec2;
+    use rltk::RandomNumberGenerator;
 
     use crate::{
+        core::types::{Cardinal, Int},
         test::{
             self,
             helpers::visibility::{from_ascii_expected, from_ascii_layout},
@@ -242,7 +213,13 @@ mod tests {
         util::algorithms::field_of_view::FOV,
     };
 
-    use super::symmetric_shadowcasting;
+    use super::{reference, symmetric_shadowcasting};
+
+    fn sorted(mut positions: Vec<IVec2>) -> Vec<(Int, Int)> {
+        let mut sorted: Vec<(Int, Int)> = positions.drain(..).map(|pos| (pos.y, pos.x)).collect();
+        sorted.sort_unstable();
+        sorted
+    }
 
     #[test]
     fn symmetric_shadowcasting_tests() {
@@ -260,7 +237,7 @@ mod tests {
 
             let mut visible_positions =
                 symmetric_shadowcasting(&origin, &|pos| fov.sees(pos, case.cardinal), &|pos| {
-                    map.is_blocking(pos)
+                    map.is_opaque(pos)
                 });
 
             visible_positions.sort_by(pos_sorter);
@@ -273,4 +250,89 @@ mod tests {
             );
         }
     }
+
+    #[test]
+    fn matches_the_reference_on_test_maps() {
+        for case in test::visibility::cases() {
+            let (origin, map) = from_ascii_layout(&case.layout);
+            let fov = FOV::Quadratic(case.range, case.a, case.b);
+            let is_visible = |pos: &IVec2| fov.sees(pos, case.cardinal);
+            let is_blocking = |pos: &IVec2| map.is_opaque(pos);
+
+            assert_eq!(
+                sorted(reference::symmetric_shadowcasting(
+                    &origin,
+                    &is_visible,
+                    &is_blocking
+                )),
+                sorted(symmetric_shadowcasting(&origin, &is_visible, &is_blocking)),
+                "Error in case: {:?}, range: {}, \n {}",
+                case.cardinal,
+                case.range,
+                case.layout
+            );
+        }
+    }
+
+    #[test]
+    fn matches_the_reference_on_random_maps() {
+        let mut rng = RandomNumberGenerator::seeded(43);
+        let cardinals = [
+            Cardinal::North,
+            Cardinal::NorthEast,
+            Cardinal::East,
+            Cardinal::SouthEast,
+            Cardinal::South,
+            Cardinal::SouthWest,
+            Cardinal::West,
+            Cardinal::NorthWest,
+        ];
+
+        for trial in 0..300 {
+            let size = IVec2::new(rng.range(5, 40), rng.range(5, 40));
+            let offset = IVec2::new(rng.range(-10, 10), rng.range(-10, 10));
+            let density = rng.range(0, 40);
+            let mut walls: HashSet<IVec2> = HashSet::new();
+            for y in 0..size.y {
+                for x in 0..size.x {
+                    if rng.range(0, 100) < density {
+                        walls.insert(offset + IVec2::new(x, y));
+                    }
+                }
+            }
+            let origin = offset + IVec2::new(rng.range(0, size.x), rng.range(0, size.y));
+            walls.remove(&origin);
+
+            let range = rng.range(0, 25);
+            let fov = match trial % 4 {
+                0 => FOV::Omnidirectional(range),
+                1 => FOV::Cone(range, rng.range(20, 300) as f32 / 100.0),
+                2 => FOV::Quadratic(range, rng.range(0, 100) as f32 / 100.0, -1.5),
+                _ => FOV::Infinite,
+            };
+            let cardinal = cardinals[rng.range(0, 8) as usize];
+            let is_visible = |pos: &IVec2| fov.sees(pos, cardinal);
+            let is_blocking = |pos: &IVec2| {
+                let local = *pos - offset;
+                walls.contains(pos)
+                    || local.x < 0
+                    || local.y < 0
+                    || local.x >= size.x
+                    || local.y >= size.y
+            };
+
+            assert_eq!(
+                sorted(reference::symmetric_shadowcasting(
+                    &origin,
+                    &is_visible,
+                    &is_blocking
+                )),
+                sorted(symmetric_shadowcasting(&origin, &is_visible, &is_blocking)),
+                "Error in trial {} with {:?} facing {:?}",
+                trial,
+                fov,
+                cardinal
+            );
+        }
+    }
 }
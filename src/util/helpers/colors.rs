@@ -6,3 +6,17 @@ pub fn greyscale(color: &Color) -> Color {
     let linear = (color.r() * 0.2126) + (color.g() * 0.7152) + (color.b() * 0.0722);
     Color::rgb(linear, linear, linear)
 }
+
+/// Darkens a color by how little light falls on it and tints it towards the light's color.
+/// Some brightness is always kept so seen tiles never turn fully black.
+#[inline]
+#[must_use]
+pub fn shade(color: &Color, light: &Color, intensity: f32) -> Color {
+    const MIN_BRIGHTNESS: f32 = 0.3;
+    let brightness = MIN_BRIGHTNESS + (1.0 - MIN_BRIGHTNESS) * intensity.clamp(0.0, 1.0);
+    Color::rgb(
+        color.r() * light.r() * brightness,
+        color.g() * light.g() * brightness,
+        color.b() * light.b() * brightness,
+    )
+}
@@ -0,0 +1,158 @@
+use std::iter::FromIterator;
+
+use bevy::math::IVec2;
+
+use crate::core::types::{Index, Int};
+
+const BLOCK_BITS: usize = u64::BITS as usize;
+
+/// Set of grid positions stored as a bitset over a fixed rectangle. Positions
+/// outside the rectangle are never contained and cannot be inserted.
+#[derive(Debug, Clone, Default)]
+pub struct GridSet {
+    min: IVec2,
+    width: Int,
+    height: Int,
+    blocks: Vec<u64>,
+}
+
+impl GridSet {
+    /// Empty set over the rectangle from `min` to `max`, both included.
+    pub fn new(min: IVec2, max: IVec2) -> Self {
+        let size = (max - min + IVec2::ONE).max(IVec2::ZERO);
+        let tiles = (size.x * size.y) as Index;
+        Self {
+            min,
+            width: size.x,
+            height: size.y,
+            blocks: vec![0; (tiles + BLOCK_BITS - 1) / BLOCK_BITS],
+        }
+    }
+
+    fn index(&self, pos: &IVec2) -> Option<Index> {
+        let local = *pos - self.min;
+        if local.x < 0 || local.y < 0 || local.x >= self.width || local.y >= self.height {
+            return None;
+        }
+        Some((local.y * self.width + local.x) as Index)
+    }
+
+    /// Adds `pos`. Returns whether it was newly added.
+    pub fn insert(&mut self, pos: IVec2) -> bool {
+        let index = match self.index(&pos) {
+            Some(index) => index,
+            None => return false,
+        };
+        let (block, bit) = (index / BLOCK_BITS, 1 << (index % BLOCK_BITS));
+        if self.blocks[block] & bit != 0 {
+            return false;
+        }
+        self.blocks[block] |= bit;
+        true
+    }
+
+    pub fn contains(&self, pos: &IVec2) -> bool {
+        self.index(pos).map_or(false, |index| {
+            self.blocks[index / BLOCK_BITS] & (1 << (index % BLOCK_BITS)) != 0
+        })
+    }
+
+    pub fn iter(&self) -> GridSetIter {
+        GridSetIter {
+            set: self,
+            index: 0,
+        }
+    }
+}
+
+impl FromIterator<IVec2> for GridSet {
+    /// Collects into a set just large enough to hold every position.
+    fn from_iter<I: IntoIterator<Item = IVec2>>(positions: I) -> Self {
+        let positions: Vec<IVec2> = positions.into_iter().collect();
+        let bounds = positions
+            .iter()
+            .map(|pos| (*pos, *pos))
+            .reduce(|(min_a, max_a), (min_b, max_b)| (min_a.min(min_b), max_a.max(max_b)));
+        let mut set = match bounds {
+            Some((min, max)) => GridSet::new(min, max),
+            None => GridSet::default(),
+        };
+        for pos in positions {
+            set.insert(pos);
+        }
+        set
+    }
+}
+
+pub struct GridSetIter<'a> {
+    set: &'a GridSet,
+    index: Index,
+}
+
+impl<'a> Iterator for GridSetIter<'a> {
+    type Item = IVec2;
+
+    fn next(&mut self) -> Option<IVec2> {
+        let tiles = (self.set.width * self.set.height) as Index;
+        while self.index < tiles {
+            let block = self.set.blocks[self.index / BLOCK_BITS] >> (self.index % BLOCK_BITS);
+            if block == 0 {
+                // Nothing left in this block, skip to the next one.
+                self.index += BLOCK_BITS - self.index % BLOCK_BITS;
+                continue;
+            }
+            self.index += block.trailing_zeros() as Index;
+            let index = self.index as Int;
+            self.index += 1;
+            let local = IVec2::new(index % self.set.width, index / self.set.width);
+            return Some(self.set.min + local);
+        }
+        None
+    }
+}
+
+impl<'a> IntoIterator for &'a GridSet {
+    type Item = IVec2;
+    type IntoIter = GridSetIter<'a>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::math::IVec2;
+
+    use super::GridSet;
+
+    #[test]
+    fn holds_only_what_fits_its_bounds() {
+        let mut set = GridSet::new(IVec2::new(-2, -2), IVec2::new(10, 10));
+        assert!(set.insert(IVec2::new(-2, -2)));
+        assert!(set.insert(IVec2::new(10, 10)));
+        assert!(set.insert(IVec2::new(3, 7)));
+        assert!(!set.insert(IVec2::new(3, 7)));
+        assert!(!set.insert(IVec2::new(11, 0)));
+
+        assert!(set.contains(&IVec2::new(3, 7)));
+        assert!(!set.contains(&IVec2::new(7, 3)));
+        assert!(!set.contains(&IVec2::new(-50, 0)));
+        assert_eq!(
+            vec![IVec2::new(-2, -2), IVec2::new(3, 7), IVec2::new(10, 10)],
+            set.iter().collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn collects_from_any_positions() {
+        let positions = vec![IVec2::new(100, -4), IVec2::new(-30, 60), IVec2::new(0, 0)];
+        let set: GridSet = positions.iter().copied().collect();
+
+        assert_eq!(positions.len(), set.iter().count());
+        for pos in &positions {
+            assert!(set.contains(pos));
+        }
+        assert_eq!(None, GridSet::default().iter().next());
+    }
+}
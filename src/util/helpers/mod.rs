@@ -6,5 +6,8 @@ pub mod colors;
 mod cp437;
 pub use cp437::cp437;
 
+mod grid_set;
+pub use grid_set::GridSet;
+
 mod rotate_grid;
 pub use rotate_grid::GridRotator;
@@ -1,3 +1,2 @@
 pub mod algorithms;
 pub mod helpers;
-pub mod math;
@@ -0,0 +1,111 @@
+use bevy::prelude::*;
+
+use crate::core::types::Int;
+
+use super::{TileType, WorldMap};
+
+/// Kind of the item that opens any locked door, used up in the process.
+pub const KEY_ITEM: &str = "key";
+
+/// Sent when a tile changes in a way that affects sight or movement.
+#[derive(Debug, Clone, Copy)]
+pub struct TerrainChangedEvent {
+    pub position: IVec2,
+}
+
+/// Actors that open doors by walking into them.
+#[derive(Debug, Component)]
+pub struct OpensDoors;
+
+#[derive(Debug, Default, Component)]
+pub struct Keyring {
+    pub keys: Int,
+}
+
+/// Opens a closed door, or a locked one when there is a key to spend on it.
+/// Returns whether the door opened.
+pub fn open_door(world_map: &mut WorldMap, pos: &IVec2, keyring: Option<&mut Keyring>) -> bool {
+    let mut tile = match world_map.tile_at_mut(pos) {
+        Some(tile) => tile,
+        None => return false,
+    };
+    match tile.which() {
+        TileType::CLOSED_DOOR => (),
+        TileType::LOCKED_DOOR => match keyring {
+            Some(keyring) if keyring.keys > 0 => keyring.keys -= 1,
+            _ => return false,
+        },
+        _ => return false,
+    }
+    tile.set_type(TileType::OPEN_DOOR);
+    true
+}
+
+/// Shuts an open door. Returns whether the door closed.
+pub fn close_door(world_map: &mut WorldMap, pos: &IVec2) -> bool {
+    match world_map.tile_at_mut(pos) {
+        Some(mut tile) if tile.which() == TileType::OPEN_DOOR => {
+            tile.set_type(TileType::CLOSED_DOOR);
+            true
+        }
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::math::IVec2;
+
+    use crate::{
+        test::helpers::visibility::from_ascii_layout,
+        world::{TileType, WorldMap},
+    };
+
+    use super::{close_door, open_door, Keyring};
+
+    fn door_map(door: TileType) -> (IVec2, WorldMap) {
+        let (_, mut area) = from_ascii_layout(
+            "#####
+             #@..#
+             #####",
+        );
+        let pos = IVec2::new(2, 1);
+        area.set_tile(&pos, door);
+        let mut world_map = WorldMap::default();
+        world_map.insert_offset(&IVec2::ZERO, area);
+        (pos, world_map)
+    }
+
+    #[test]
+    fn doors_block_only_while_closed() {
+        let (door, mut world_map) = door_map(TileType::CLOSED_DOOR);
+        assert!(world_map.is_blocking(&door));
+        assert!(world_map.is_opaque(&door));
+        assert!(world_map.is_closed_door(&door));
+
+        assert!(open_door(&mut world_map, &door, None));
+        assert!(!world_map.is_blocking(&door));
+        assert!(!world_map.is_opaque(&door));
+        assert!(!open_door(&mut world_map, &door, None));
+
+        assert!(close_door(&mut world_map, &door));
+        assert!(world_map.is_blocking(&door));
+        assert!(!close_door(&mut world_map, &door));
+    }
+
+    #[test]
+    fn locked_doors_need_a_key() {
+        let (door, mut world_map) = door_map(TileType::LOCKED_DOOR);
+        let mut keyring = Keyring::default();
+        assert!(!open_door(&mut world_map, &door, None));
+        assert!(!open_door(&mut world_map, &door, Some(&mut keyring)));
+
+        keyring.keys = 1;
+        assert!(open_door(&mut world_map, &door, Some(&mut keyring)));
+        assert_eq!(0, keyring.keys);
+        assert_eq!(
+            TileType::OPEN_DOOR,
+            world_map.tile_at(&door).unwrap().which()
+        );
+    }
+}
@@ -0,0 +1,125 @@
+use std::collections::HashMap;
+
+use bevy::prelude::*;
+
+use crate::{
+    actors::{Actor, Statuses},
+    ai::Awareness,
+    core::types::{Increment, Int},
+};
+
+use super::{Item, Renderable, WorldMap};
+
+/// Keeps the seeds of neighbouring depths far apart.
+const LEVEL_SEED_STRIDE: u64 = 7919;
+
+/// Sent when an actor takes the stairs. `delta` is 1 going down and -1 going up.
+#[derive(Debug, Clone, Copy)]
+pub struct LevelChangeEvent {
+    pub entity: Entity,
+    pub delta: Int,
+}
+
+#[derive(Debug, Clone)]
+pub struct MonsterSnapshot {
+    pub kind: String,
+    pub position: IVec2,
+    pub actor: Actor,
+    pub statuses: Statuses,
+    pub awareness: Awareness,
+    /// The squad entity the monster belonged to when the level was left.
+    pub squad: Option<Entity>,
+}
+
+#[derive(Debug, Clone)]
+pub struct ItemSnapshot {
+    pub item: Item,
+    pub position: IVec2,
+    pub renderable: Renderable,
+}
+
+#[derive(Debug, Clone)]
+pub struct HazardSnapshot {
+    pub kind: String,
+    pub position: IVec2,
+    pub time_left: Increment,
+    pub reach: Int,
+}
+
+/// A level the player has left, kept as it was so it can be restored on return.
+#[derive(Debug, Default)]
+pub struct StoredLevel {
+    pub world_map: WorldMap,
+    pub monsters: Vec<MonsterSnapshot>,
+    pub items: Vec<ItemSnapshot>,
+    pub hazards: Vec<HazardSnapshot>,
+}
+
+/// Every level visited so far. Only the current one lives in `WorldMap` and the ECS.
+#[derive(Debug, Default)]
+pub struct Dungeon {
+    pub seed: u64,
+    pub depth: Int,
+    levels: HashMap<Int, StoredLevel>,
+}
+
+impl Dungeon {
+    pub fn level_seed(&self, depth: Int) -> u64 {
+        self.seed
+            .wrapping_add((depth as u64).wrapping_mul(LEVEL_SEED_STRIDE))
+    }
+
+    pub fn store(&mut self, depth: Int, level: StoredLevel) {
+        self.levels.insert(depth, level);
+    }
+
+    pub fn level(&self, depth: Int) -> Option<&StoredLevel> {
+        self.levels.get(&depth)
+    }
+
+    pub fn take(&mut self, depth: Int) -> Option<StoredLevel> {
+        self.levels.remove(&depth)
+    }
+
+    pub fn is_visited(&self, depth: Int) -> bool {
+        self.levels.contains_key(&depth)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::math::IVec2;
+
+    use crate::world::{AreaGrid, WorldMap};
+
+    use super::{Dungeon, StoredLevel};
+
+    #[test]
+    fn levels_are_kept_until_taken() {
+        let mut dungeon = Dungeon::default();
+        let mut world_map = WorldMap::default();
+        world_map.insert_offset(&IVec2::ZERO, AreaGrid::new(&IVec2::new(5, 5)));
+        dungeon.store(
+            1,
+            StoredLevel {
+                world_map,
+                ..Default::default()
+            },
+        );
+
+        assert!(dungeon.is_visited(1));
+        assert!(!dungeon.is_visited(2));
+        assert!(dungeon.take(1).unwrap().world_map.bounds().is_some());
+        assert!(dungeon.take(1).is_none());
+    }
+
+    #[test]
+    fn depths_get_their_own_seed() {
+        let dungeon = Dungeon {
+            seed: 42,
+            ..Default::default()
+        };
+        assert_eq!(42, dungeon.level_seed(0));
+        assert_ne!(dungeon.level_seed(1), dungeon.level_seed(2));
+    }
+}
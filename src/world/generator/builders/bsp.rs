@@ -0,0 +1,117 @@
+use bevy::math::IVec2;
+use rltk::RandomNumberGenerator;
+
+use crate::{core::types::Int, world::generator::types::Room};
+
+use super::{apply_room, connect, filled_area, BuiltMap, MapBuilder, MapMetadata};
+
+/// Leaves are never split below this size on either axis.
+const MIN_LEAF_SIZE: Int = 8;
+const MAX_DEPTH: Int = 5;
+const MIN_ROOM_SIZE: Int = 3;
+
+/// Recursively splits the area in two, places a room in every leaf and links leaves
+/// in the order the splits produced them, so neighbouring rooms end up connected.
+pub struct BspBuilder {
+    width: Int,
+    height: Int,
+}
+
+impl BspBuilder {
+    pub fn new(width: Int, height: Int) -> Self {
+        Self { width, height }
+    }
+
+    fn split(
+        &self,
+        leaf: Room,
+        depth: Int,
+        rng: &mut RandomNumberGenerator,
+        leaves: &mut Vec<Room>,
+    ) {
+        let width = leaf.x2 - leaf.x1;
+        let height = leaf.y2 - leaf.y1;
+        let can_split_x = width >= MIN_LEAF_SIZE * 2;
+        let can_split_y = height >= MIN_LEAF_SIZE * 2;
+
+        if depth >= MAX_DEPTH || (!can_split_x && !can_split_y) {
+            leaves.push(leaf);
+            return;
+        }
+
+        let split_x = if can_split_x && can_split_y {
+            width > height || (width == height && 1 == rng.range(0, 2))
+        } else {
+            can_split_x
+        };
+
+        let (first, second) = if split_x {
+            let at = rng.range(MIN_LEAF_SIZE, width - MIN_LEAF_SIZE + 1);
+            (
+                Room::new(leaf.x1, leaf.y1, at, height),
+                Room::new(leaf.x1 + at, leaf.y1, width - at, height),
+            )
+        } else {
+            let at = rng.range(MIN_LEAF_SIZE, height - MIN_LEAF_SIZE + 1);
+            (
+                Room::new(leaf.x1, leaf.y1, width, at),
+                Room::new(leaf.x1, leaf.y1 + at, width, height - at),
+            )
+        };
+        self.split(first, depth + 1, rng, leaves);
+        self.split(second, depth + 1, rng, leaves);
+    }
+
+    /// A room inside the leaf, keeping a wall between it and the leaf edges.
+    fn room_in(&self, leaf: &Room, rng: &mut RandomNumberGenerator) -> Room {
+        let max_w = leaf.x2 - leaf.x1 - 2;
+        let max_h = leaf.y2 - leaf.y1 - 2;
+        let w = rng.range(MIN_ROOM_SIZE, max_w + 1);
+        let h = rng.range(MIN_ROOM_SIZE, max_h + 1);
+        let x = leaf.x1 + rng.range(0, max_w - w + 1);
+        let y = leaf.y1 + rng.range(0, max_h - h + 1);
+        Room::new(x, y, w, h)
+    }
+}
+
+impl MapBuilder for BspBuilder {
+    fn build(&mut self, rng: &mut RandomNumberGenerator) -> BuiltMap {
+        let mut area = filled_area(self.width, self.height);
+
+        let mut leaves: Vec<Room> = vec![];
+        let whole = Room::new(0, 0, self.width - 1, self.height - 1);
+        self.split(whole, 0, rng, &mut leaves);
+
+        let rooms: Vec<Room> = leaves.iter().map(|leaf| self.room_in(leaf, rng)).collect();
+        for room in &rooms {
+            apply_room(&mut area, room);
+        }
+        for pair in rooms.windows(2) {
+            connect(&mut area, pair[0].center(), pair[1].center(), rng);
+        }
+
+        let (start_x, start_y) = rooms[0].center();
+        let spawn_points = rooms
+            .iter()
+            .skip(1)
+            .map(|room| {
+                let (x, y) = room.center();
+                IVec2::new(x, y)
+            })
+            .collect();
+
+        BuiltMap {
+            area,
+            player_start: IVec2::new(start_x, start_y),
+            spawn_points,
+            exit: None,
+            doors: vec![],
+            markers: vec![],
+            metadata: MapMetadata {
+                name: "Binary space partition".to_string(),
+                seed: 0,
+                rooms,
+            },
+        }
+    }
+}
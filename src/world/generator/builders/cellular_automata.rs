@@ -0,0 +1,86 @@
+use bevy::math::IVec2;
+use rltk::RandomNumberGenerator;
+
+use crate::{
+    core::types::Int,
+    util::algorithms::geometry::chessboard_neighbours,
+    world::{AreaGrid, TileType},
+};
+
+use super::{
+    central_floor, filled_area, keep_largest_region, scatter_spawn_points, BuiltMap, MapBuilder,
+    MapMetadata,
+};
+
+/// Chance out of 100 for a tile to start as a wall.
+const INITIAL_WALL_CHANCE: Int = 45;
+const ITERATIONS: Int = 12;
+const SPAWN_COUNT: usize = 12;
+
+/// Caves grown by repeatedly smoothing random noise, keeping only the largest cavern.
+pub struct CellularAutomataBuilder {
+    width: Int,
+    height: Int,
+}
+
+impl CellularAutomataBuilder {
+    pub fn new(width: Int, height: Int) -> Self {
+        Self { width, height }
+    }
+
+    fn is_border(&self, pos: &IVec2) -> bool {
+        pos.x == 0 || pos.y == 0 || pos.x == self.width - 1 || pos.y == self.height - 1
+    }
+
+    fn smooth(&self, area: &AreaGrid) -> AreaGrid {
+        let mut next = area.clone();
+        for pos in area.clone() {
+            if self.is_border(&pos) {
+                continue;
+            }
+            let walls = chessboard_neighbours(&pos)
+                .filter(|neighbour| area.is_blocking(neighbour))
+                .count();
+            let tile = if walls > 4 || walls == 0 {
+                TileType::WALL
+            } else {
+                TileType::FLOOR
+            };
+            next.set_tile(&pos, tile);
+        }
+        next
+    }
+}
+
+impl MapBuilder for CellularAutomataBuilder {
+    fn build(&mut self, rng: &mut RandomNumberGenerator) -> BuiltMap {
+        let mut area = filled_area(self.width, self.height);
+        for pos in area.clone() {
+            if !self.is_border(&pos) && rng.roll_dice(1, 100) > INITIAL_WALL_CHANCE {
+                area.set_tile(&pos, TileType::FLOOR);
+            }
+        }
+
+        for _ in 0..ITERATIONS {
+            area = self.smooth(&area);
+        }
+        keep_largest_region(&mut area);
+
+        let player_start = central_floor(&area);
+        let spawn_points = scatter_spawn_points(&area, &player_start, SPAWN_COUNT, rng);
+
+        BuiltMap {
+            area,
+            player_start,
+            spawn_points,
+            exit: None,
+            doors: vec![],
+            markers: vec![],
+            metadata: MapMetadata {
+                name: "Cellular automata caves".to_string(),
+                seed: 0,
+                rooms: vec![],
+            },
+        }
+    }
+}
@@ -0,0 +1,158 @@
+use std::{
+    cmp::{max, min},
+    collections::HashSet,
+};
+
+use bevy::math::IVec2;
+use rltk::RandomNumberGenerator;
+
+use crate::{
+    core::types::Int,
+    util::algorithms::{geometry::chessboard_distance, pathfinding::dijkstra_map},
+    world::{generator::types::Room, AreaGrid, TileType},
+};
+
+/// Minimum distance between the player start and any spawn point.
+const SPAWN_CLEARANCE: Int = 6;
+
+/// A wall-filled area, built from the usual width and height rather than `AreaGrid::new`'s rows and columns.
+pub fn filled_area(width: Int, height: Int) -> AreaGrid {
+    AreaGrid::new(&IVec2::new(height, width))
+}
+
+pub fn apply_room(area: &mut AreaGrid, room: &Room) {
+    for y in room.y1 + 1..=room.y2 {
+        for x in room.x1 + 1..=room.x2 {
+            area.set_tile(&IVec2::new(x, y), TileType::FLOOR);
+        }
+    }
+}
+
+pub fn apply_horizontal_tunnel(area: &mut AreaGrid, x1: Int, x2: Int, y: Int) {
+    for x in min(x1, x2)..=max(x1, x2) {
+        area.set_tile(&IVec2::new(x, y), TileType::FLOOR);
+    }
+}
+
+pub fn apply_vertical_tunnel(area: &mut AreaGrid, y1: Int, y2: Int, x: Int) {
+    for y in min(y1, y2)..=max(y1, y2) {
+        area.set_tile(&IVec2::new(x, y), TileType::FLOOR);
+    }
+}
+
+/// Digs an L-shaped corridor between two points, bending one way or the other at random.
+pub fn connect(
+    area: &mut AreaGrid,
+    from: (Int, Int),
+    to: (Int, Int),
+    rng: &mut RandomNumberGenerator,
+) {
+    let ((from_x, from_y), (to_x, to_y)) = (from, to);
+    if 1 == rng.range(0, 2) {
+        apply_horizontal_tunnel(area, from_x, to_x, from_y);
+        apply_vertical_tunnel(area, from_y, to_y, to_x);
+    } else {
+        apply_vertical_tunnel(area, from_y, to_y, from_x);
+        apply_horizontal_tunnel(area, from_x, to_x, to_y);
+    }
+}
+
+pub fn floor_positions(area: &AreaGrid) -> Vec<IVec2> {
+    (0..area.tiles.len())
+        .filter(|index| area.tiles[*index] == TileType::FLOOR)
+        .map(|index| area.index_to_point(index))
+        .collect()
+}
+
+/// Opens a passage through the touching borders of two areas placed side by side,
+/// on a random row where both have floor. Returns the row that was opened.
+pub fn open_seam(
+    west: &mut AreaGrid,
+    east: &mut AreaGrid,
+    rng: &mut RandomNumberGenerator,
+) -> Option<Int> {
+    let has_floor =
+        |area: &AreaGrid, y: Int| (0..area.width).any(|x| !area.is_blocking(&IVec2::new(x, y)));
+    let rows: Vec<Int> = (1..min(west.height, east.height) - 1)
+        .filter(|y| has_floor(west, *y) && has_floor(east, *y))
+        .collect();
+    let y = *rng.random_slice_entry(&rows)?;
+
+    let west_floor = (0..west.width)
+        .rev()
+        .find(|x| !west.is_blocking(&IVec2::new(*x, y)))?;
+    let east_floor = (0..east.width).find(|x| !east.is_blocking(&IVec2::new(*x, y)))?;
+    apply_horizontal_tunnel(west, west_floor, west.width - 1, y);
+    apply_horizontal_tunnel(east, 0, east_floor, y);
+    Some(y)
+}
+
+/// Every walkable position connected to `start`. Doors count as walkable whether or not they are shut.
+pub fn reachable_from(area: &AreaGrid, start: &IVec2) -> HashSet<IVec2> {
+    let is_door = |pos: &IVec2| {
+        area.tile_at(pos)
+            .map_or(false, |tile| tile.which().is_door())
+    };
+    dijkstra_map(&[*start], Int::MAX, &|pos| {
+        if area.is_point_in_bounds(pos) && (!area.is_blocking(pos) || is_door(pos)) {
+            Some(1)
+        } else {
+            None
+        }
+    })
+    .into_keys()
+    .collect()
+}
+
+/// Walls off every floor tile that isn't part of the largest connected region.
+pub fn keep_largest_region(area: &mut AreaGrid) {
+    let mut visited: HashSet<IVec2> = HashSet::new();
+    let mut largest: HashSet<IVec2> = HashSet::new();
+
+    for start in floor_positions(area) {
+        if visited.contains(&start) {
+            continue;
+        }
+        let region = reachable_from(area, &start);
+        visited.extend(region.iter().copied());
+        if region.len() > largest.len() {
+            largest = region;
+        }
+    }
+
+    for pos in floor_positions(area) {
+        if !largest.contains(&pos) {
+            area.set_tile(&pos, TileType::WALL);
+        }
+    }
+}
+
+/// Floor tile closest to the middle of the area.
+pub fn central_floor(area: &AreaGrid) -> IVec2 {
+    let center = IVec2::new(area.width / 2, area.height / 2);
+    floor_positions(area)
+        .into_iter()
+        .min_by_key(|pos| chessboard_distance(pos, &center))
+        .unwrap_or(center)
+}
+
+/// Random floor tiles reachable from the start but not too close to it.
+pub fn scatter_spawn_points(
+    area: &AreaGrid,
+    start: &IVec2,
+    count: usize,
+    rng: &mut RandomNumberGenerator,
+) -> Vec<IVec2> {
+    let mut candidates: Vec<IVec2> = reachable_from(area, start)
+        .into_iter()
+        .filter(|pos| chessboard_distance(pos, start) >= SPAWN_CLEARANCE)
+        .collect();
+    candidates.sort_by_key(|pos| (pos.y, pos.x));
+
+    let mut spawn_points = vec![];
+    while spawn_points.len() < count && !candidates.is_empty() {
+        let index = rng.random_slice_index(&candidates).unwrap();
+        spawn_points.push(candidates.swap_remove(index));
+    }
+    spawn_points
+}
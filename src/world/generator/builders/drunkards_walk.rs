@@ -0,0 +1,73 @@
+use bevy::math::IVec2;
+use rltk::RandomNumberGenerator;
+
+use crate::{
+    core::types::{Int, Real},
+    world::TileType,
+};
+
+use super::{
+    filled_area, floor_positions, scatter_spawn_points, BuiltMap, MapBuilder, MapMetadata,
+};
+
+/// Portion of the area to dig out before stopping.
+const FLOOR_RATIO: Real = 0.4;
+const STEPS_PER_DIGGER: Int = 400;
+const SPAWN_COUNT: usize = 12;
+
+/// Diggers stumbling around from already dug tiles until enough of the area is open.
+pub struct DrunkardsWalkBuilder {
+    width: Int,
+    height: Int,
+}
+
+impl DrunkardsWalkBuilder {
+    pub fn new(width: Int, height: Int) -> Self {
+        Self { width, height }
+    }
+
+    fn is_interior(&self, pos: &IVec2) -> bool {
+        pos.x > 0 && pos.y > 0 && pos.x < self.width - 1 && pos.y < self.height - 1
+    }
+}
+
+impl MapBuilder for DrunkardsWalkBuilder {
+    fn build(&mut self, rng: &mut RandomNumberGenerator) -> BuiltMap {
+        let mut area = filled_area(self.width, self.height);
+        let player_start = IVec2::new(self.width / 2, self.height / 2);
+        area.set_tile(&player_start, TileType::FLOOR);
+
+        let target_floor = (FLOOR_RATIO * (self.width * self.height) as Real) as usize;
+        let steps = [IVec2::X, -IVec2::X, IVec2::Y, -IVec2::Y];
+
+        let mut floor = floor_positions(&area);
+        while floor.len() < target_floor {
+            let mut digger = *rng.random_slice_entry(&floor).unwrap();
+            for _ in 0..STEPS_PER_DIGGER {
+                let next = digger + *rng.random_slice_entry(&steps).unwrap();
+                if !self.is_interior(&next) {
+                    continue;
+                }
+                digger = next;
+                area.set_tile(&digger, TileType::FLOOR);
+            }
+            floor = floor_positions(&area);
+        }
+
+        let spawn_points = scatter_spawn_points(&area, &player_start, SPAWN_COUNT, rng);
+
+        BuiltMap {
+            area,
+            player_start,
+            spawn_points,
+            exit: None,
+            doors: vec![],
+            markers: vec![],
+            metadata: MapMetadata {
+                name: "Drunkard's walk".to_string(),
+                seed: 0,
+                rooms: vec![],
+            },
+        }
+    }
+}
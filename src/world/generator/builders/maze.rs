@@ -0,0 +1,91 @@
+use bevy::math::IVec2;
+use rltk::RandomNumberGenerator;
+
+use crate::{core::types::Int, world::TileType};
+
+use super::{filled_area, scatter_spawn_points, BuiltMap, MapBuilder, MapMetadata};
+
+const SPAWN_COUNT: usize = 8;
+
+/// Perfect maze carved with a randomized depth-first search. Cells sit on odd
+/// coordinates and the walls between them are knocked down as the search advances.
+pub struct MazeBuilder {
+    width: Int,
+    height: Int,
+}
+
+impl MazeBuilder {
+    pub fn new(width: Int, height: Int) -> Self {
+        Self { width, height }
+    }
+
+    fn columns(&self) -> Int {
+        (self.width - 1) / 2
+    }
+
+    fn rows(&self) -> Int {
+        (self.height - 1) / 2
+    }
+
+    fn cell_position(cell: &IVec2) -> IVec2 {
+        IVec2::new(cell.x * 2 + 1, cell.y * 2 + 1)
+    }
+
+    fn is_cell(&self, cell: &IVec2) -> bool {
+        cell.x >= 0 && cell.y >= 0 && cell.x < self.columns() && cell.y < self.rows()
+    }
+}
+
+impl MapBuilder for MazeBuilder {
+    fn build(&mut self, rng: &mut RandomNumberGenerator) -> BuiltMap {
+        let mut area = filled_area(self.width, self.height);
+        let mut visited = vec![false; (self.columns() * self.rows()) as usize];
+        let cell_index = |cell: &IVec2| (cell.y * self.columns() + cell.x) as usize;
+
+        let steps = [IVec2::X, -IVec2::X, IVec2::Y, -IVec2::Y];
+        let start = IVec2::ZERO;
+        visited[cell_index(&start)] = true;
+        area.set_tile(&Self::cell_position(&start), TileType::FLOOR);
+
+        let mut stack = vec![start];
+        while let Some(cell) = stack.last().copied() {
+            let unvisited: Vec<IVec2> = steps
+                .iter()
+                .map(|step| cell + *step)
+                .filter(|next| self.is_cell(next) && !visited[cell_index(next)])
+                .collect();
+
+            match rng.random_slice_entry(&unvisited) {
+                Some(next) => {
+                    let next = *next;
+                    visited[cell_index(&next)] = true;
+                    let from = Self::cell_position(&cell);
+                    let to = Self::cell_position(&next);
+                    area.set_tile(&((from + to) / 2), TileType::FLOOR);
+                    area.set_tile(&to, TileType::FLOOR);
+                    stack.push(next);
+                }
+                None => {
+                    stack.pop();
+                }
+            }
+        }
+
+        let player_start = Self::cell_position(&start);
+        let spawn_points = scatter_spawn_points(&area, &player_start, SPAWN_COUNT, rng);
+
+        BuiltMap {
+            area,
+            player_start,
+            spawn_points,
+            exit: None,
+            doors: vec![],
+            markers: vec![],
+            metadata: MapMetadata {
+                name: "Maze".to_string(),
+                seed: 0,
+                rooms: vec![],
+            },
+        }
+    }
+}
@@ -0,0 +1,333 @@
+mod common;
+pub use common::*;
+
+mod bsp;
+pub use bsp::BspBuilder;
+
+mod cellular_automata;
+pub use cellular_automata::CellularAutomataBuilder;
+
+mod drunkards_walk;
+pub use drunkards_walk::DrunkardsWalkBuilder;
+
+mod maze;
+pub use maze::MazeBuilder;
+
+mod rooms_and_corridors;
+pub use rooms_and_corridors::RoomsAndCorridorsBuilder;
+
+mod wave_function_collapse;
+pub use wave_function_collapse::WaveFunctionCollapseBuilder;
+
+use bevy::math::IVec2;
+use rltk::RandomNumberGenerator;
+use serde::Deserialize;
+
+use crate::{
+    core::types::{Int, Real},
+    util::helpers::deserialize,
+    world::{
+        generator::{post_processing::PostProcess, prefab::Marker, types::Room},
+        AreaGrid, FULL_LIGHT,
+    },
+};
+
+pub trait MapBuilder {
+    fn build(&mut self, rng: &mut RandomNumberGenerator) -> BuiltMap;
+}
+
+#[derive(Debug, Clone)]
+pub struct MapMetadata {
+    pub name: String,
+    pub seed: u64,
+    pub rooms: Vec<Room>,
+}
+
+#[derive(Debug, Clone)]
+pub struct BuiltMap {
+    pub area: AreaGrid,
+    pub player_start: IVec2,
+    pub spawn_points: Vec<IVec2>,
+    pub exit: Option<IVec2>,
+    pub doors: Vec<IVec2>,
+    /// Spawns requested by prefabs, on the tiles they were stamped onto.
+    pub markers: Vec<(IVec2, Marker)>,
+    pub metadata: MapMetadata,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Deserialize)]
+pub enum BuilderKind {
+    RoomsAndCorridors,
+    Bsp,
+    CellularAutomata,
+    DrunkardsWalk,
+    Maze,
+    WaveFunctionCollapse,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct GeneratorSettings {
+    pub builder: BuilderKind,
+    /// A fresh seed is rolled on every run when left out.
+    pub seed: Option<u64>,
+    pub width: Int,
+    pub height: Int,
+    #[serde(default)]
+    pub post_processing: Vec<PostProcess>,
+    /// Areas generated east of the first one, each joined to the one before it.
+    #[serde(default)]
+    pub extra_areas: Int,
+    /// Builder for each depth, starting at the top. The last one is kept for deeper
+    /// levels, and `builder` is used when the list is empty.
+    #[serde(default)]
+    pub depth_builders: Vec<BuilderKind>,
+    /// Ambient light for each depth, starting at the top, with the last one kept for
+    /// deeper levels. Levels are fully lit when the list is empty.
+    #[serde(default)]
+    pub ambient_light: Vec<Real>,
+}
+
+impl Default for GeneratorSettings {
+    fn default() -> Self {
+        deserialize("assets/data/generator.ron")
+    }
+}
+
+impl GeneratorSettings {
+    pub fn ambient_light_at(&self, depth: Int) -> Real {
+        let index = (depth.max(0) as usize).min(self.ambient_light.len().saturating_sub(1));
+        self.ambient_light.get(index).copied().unwrap_or(FULL_LIGHT)
+    }
+
+    pub fn builder_at(&self, depth: Int) -> BuilderKind {
+        let index = (depth.max(0) as usize).min(self.depth_builders.len().saturating_sub(1));
+        self.depth_builders
+            .get(index)
+            .copied()
+            .unwrap_or(self.builder)
+    }
+}
+
+pub fn builder_for(kind: BuilderKind, width: Int, height: Int) -> Box<dyn MapBuilder> {
+    match kind {
+        BuilderKind::RoomsAndCorridors => Box::new(RoomsAndCorridorsBuilder::new(width, height)),
+        BuilderKind::Bsp => Box::new(BspBuilder::new(width, height)),
+        BuilderKind::CellularAutomata => Box::new(CellularAutomataBuilder::new(width, height)),
+        BuilderKind::DrunkardsWalk => Box::new(DrunkardsWalkBuilder::new(width, height)),
+        BuilderKind::Maze => Box::new(MazeBuilder::new(width, height)),
+        BuilderKind::WaveFunctionCollapse => {
+            Box::new(WaveFunctionCollapseBuilder::new(width, height))
+        }
+    }
+}
+
+pub fn build_map(settings: &GeneratorSettings) -> BuiltMap {
+    let seed = settings
+        .seed
+        .unwrap_or_else(|| RandomNumberGenerator::new().next_u64());
+    let mut rng = RandomNumberGenerator::seeded(seed);
+
+    let mut built = builder_for(settings.builder, settings.width, settings.height).build(&mut rng);
+    for step in &settings.post_processing {
+        step.apply(&mut built, &mut rng);
+    }
+    built.metadata.seed = seed;
+    built
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::math::IVec2;
+    use rltk::RandomNumberGenerator;
+
+    use crate::{
+        core::types::Int,
+        world::{generator::post_processing::PostProcess, TileType, FULL_LIGHT},
+    };
+
+    use super::{build_map, open_seam, reachable_from, BuilderKind, BuiltMap, GeneratorSettings};
+
+    const WIDTH: Int = 80;
+    const HEIGHT: Int = 50;
+    const SEEDS: [u64; 4] = [1, 7, 42, 1337];
+
+    fn build(builder: BuilderKind, seed: u64) -> BuiltMap {
+        build_map(&GeneratorSettings {
+            builder,
+            seed: Some(seed),
+            width: WIDTH,
+            height: HEIGHT,
+            post_processing: vec![],
+            extra_areas: 0,
+            depth_builders: vec![],
+            ambient_light: vec![],
+        })
+    }
+
+    fn assert_bounded(map: &BuiltMap) {
+        let area = &map.area;
+        assert_eq!(WIDTH, area.width);
+        assert_eq!(HEIGHT, area.height);
+        assert_eq!((WIDTH * HEIGHT) as usize, area.tiles.len());
+
+        for x in 0..WIDTH {
+            for y in [0, HEIGHT - 1] {
+                assert!(
+                    area.is_blocking(&IVec2::new(x, y)),
+                    "Open border at {}, {}",
+                    x,
+                    y
+                );
+            }
+        }
+        for y in 0..HEIGHT {
+            for x in [0, WIDTH - 1] {
+                assert!(
+                    area.is_blocking(&IVec2::new(x, y)),
+                    "Open border at {}, {}",
+                    x,
+                    y
+                );
+            }
+        }
+    }
+
+    fn assert_connected(map: &BuiltMap) {
+        let area = &map.area;
+        assert!(!area.is_blocking(&map.player_start));
+
+        let reachable = reachable_from(area, &map.player_start);
+        for pos in area.clone() {
+            if area.tile_at(&pos).unwrap().which() == TileType::FLOOR {
+                assert!(reachable.contains(&pos), "Unreachable floor at {:?}", pos);
+            }
+        }
+        for spawn_point in &map.spawn_points {
+            assert!(reachable.contains(spawn_point));
+        }
+    }
+
+    fn check(builder: BuilderKind) {
+        for seed in SEEDS {
+            let map = build(builder, seed);
+            assert_bounded(&map);
+            assert_connected(&map);
+            assert_eq!(seed, map.metadata.seed);
+        }
+    }
+
+    #[test]
+    fn rooms_and_corridors() {
+        check(BuilderKind::RoomsAndCorridors);
+    }
+
+    #[test]
+    fn bsp() {
+        check(BuilderKind::Bsp);
+    }
+
+    #[test]
+    fn cellular_automata() {
+        check(BuilderKind::CellularAutomata);
+    }
+
+    #[test]
+    fn drunkards_walk() {
+        check(BuilderKind::DrunkardsWalk);
+    }
+
+    #[test]
+    fn maze() {
+        check(BuilderKind::Maze);
+    }
+
+    #[test]
+    fn wave_function_collapse() {
+        check(BuilderKind::WaveFunctionCollapse);
+    }
+
+    #[test]
+    fn post_processing_on_every_builder() {
+        let builders = [
+            BuilderKind::RoomsAndCorridors,
+            BuilderKind::Bsp,
+            BuilderKind::CellularAutomata,
+            BuilderKind::DrunkardsWalk,
+            BuilderKind::Maze,
+            BuilderKind::WaveFunctionCollapse,
+        ];
+        for builder in builders {
+            let map = build_map(&GeneratorSettings {
+                builder,
+                seed: Some(11),
+                width: WIDTH,
+                height: HEIGHT,
+                post_processing: vec![
+                    PostProcess::ThickenBorders(2),
+                    PostProcess::CullUnreachable,
+                    PostProcess::PlaceDoors,
+                    PostProcess::PlacePrefabs(2),
+                    PostProcess::PlaceStartAndExit(10),
+                ],
+                extra_areas: 0,
+                depth_builders: vec![],
+                ambient_light: vec![],
+            });
+            assert_bounded(&map);
+            assert_connected(&map);
+
+            let exit = map.exit.expect("Missing exit");
+            assert!(reachable_from(&map.area, &map.player_start).contains(&exit));
+        }
+    }
+
+    #[test]
+    fn seams_join_neighbouring_areas() {
+        let mut west = build(BuilderKind::Bsp, 5);
+        let mut east = build(BuilderKind::CellularAutomata, 6);
+        let mut rng = RandomNumberGenerator::seeded(1);
+        let row = open_seam(&mut west.area, &mut east.area, &mut rng).expect("No shared row");
+
+        assert!(!west.area.is_blocking(&IVec2::new(WIDTH - 1, row)));
+        assert!(!east.area.is_blocking(&IVec2::new(0, row)));
+        assert!(
+            reachable_from(&west.area, &west.player_start).contains(&IVec2::new(WIDTH - 1, row))
+        );
+        assert!(reachable_from(&east.area, &east.player_start).contains(&IVec2::new(0, row)));
+    }
+
+    #[test]
+    fn builders_follow_depth() {
+        let settings = GeneratorSettings {
+            builder: BuilderKind::Maze,
+            seed: None,
+            width: WIDTH,
+            height: HEIGHT,
+            post_processing: vec![],
+            extra_areas: 0,
+            depth_builders: vec![BuilderKind::Bsp, BuilderKind::CellularAutomata],
+            ambient_light: vec![0.6, 0.2],
+        };
+        assert_eq!(BuilderKind::Bsp, settings.builder_at(0));
+        assert_eq!(BuilderKind::CellularAutomata, settings.builder_at(1));
+        assert_eq!(BuilderKind::CellularAutomata, settings.builder_at(5));
+        assert_eq!(0.6, settings.ambient_light_at(0));
+        assert_eq!(0.2, settings.ambient_light_at(5));
+
+        let settings = GeneratorSettings {
+            depth_builders: vec![],
+            ambient_light: vec![],
+            ..settings
+        };
+        assert_eq!(BuilderKind::Maze, settings.builder_at(3));
+        assert_eq!(FULL_LIGHT, settings.ambient_light_at(3));
+    }
+
+    #[test]
+    fn same_seed_same_map() {
+        let first = build(BuilderKind::CellularAutomata, 3);
+        let second = build(BuilderKind::CellularAutomata, 3);
+        assert_eq!(first.area.tiles, second.area.tiles);
+        assert_eq!(first.player_start, second.player_start);
+    }
+}
@@ -0,0 +1,72 @@
+use bevy::math::IVec2;
+use rltk::RandomNumberGenerator;
+
+use crate::{core::types::Int, world::generator::types::Room};
+
+use super::{apply_room, connect, filled_area, BuiltMap, MapBuilder, MapMetadata};
+
+const MAX_ROOMS: Int = 30;
+const MIN_SIZE: Int = 6;
+const MAX_SIZE: Int = 10;
+
+pub struct RoomsAndCorridorsBuilder {
+    width: Int,
+    height: Int,
+}
+
+impl RoomsAndCorridorsBuilder {
+    pub fn new(width: Int, height: Int) -> Self {
+        Self { width, height }
+    }
+}
+
+impl MapBuilder for RoomsAndCorridorsBuilder {
+    fn build(&mut self, rng: &mut RandomNumberGenerator) -> BuiltMap {
+        let mut area = filled_area(self.width, self.height);
+        let mut rooms: Vec<Room> = Vec::new();
+
+        for _ in 0..MAX_ROOMS {
+            let w = rng.range(MIN_SIZE, MAX_SIZE);
+            let h = rng.range(MIN_SIZE, MAX_SIZE);
+            let x = rng.roll_dice(1, self.width - w - 1) - 1;
+            let y = rng.roll_dice(1, self.height - h - 1) - 1;
+            let new_room = Room::new(x, y, w, h);
+            if rooms
+                .iter()
+                .any(|other_room| new_room.intersect(other_room))
+            {
+                continue;
+            }
+
+            apply_room(&mut area, &new_room);
+            if let Some(previous_room) = rooms.last() {
+                connect(&mut area, previous_room.center(), new_room.center(), rng);
+            }
+            rooms.push(new_room);
+        }
+
+        let (start_x, start_y) = rooms[0].center();
+        let spawn_points = rooms
+            .iter()
+            .skip(1)
+            .map(|room| {
+                let (x, y) = room.center();
+                IVec2::new(x, y)
+            })
+            .collect();
+
+        BuiltMap {
+            area,
+            player_start: IVec2::new(start_x, start_y),
+            spawn_points,
+            exit: None,
+            doors: vec![],
+            markers: vec![],
+            metadata: MapMetadata {
+                name: "Rooms and corridors".to_string(),
+                seed: 0,
+                rooms,
+            },
+        }
+    }
+}
@@ -0,0 +1,89 @@
+use rltk::RandomNumberGenerator;
+
+use crate::{
+    core::types::{Int, Real},
+    world::{
+        generator::wfc::{collapse, Patterns, Sample, WfcSettings},
+        AreaGrid,
+    },
+};
+
+use super::{
+    central_floor, floor_positions, keep_largest_region, scatter_spawn_points, BuiltMap,
+    CellularAutomataBuilder, MapBuilder, MapMetadata,
+};
+
+const MAX_ATTEMPTS: Int = 10;
+/// Share of the area that has to stay walkable once disconnected pockets are walled off.
+const MIN_FLOOR_RATIO: Real = 0.2;
+const SPAWN_COUNT: usize = 10;
+
+/// Areas that copy the local look of hand-drawn samples, using the overlapping
+/// Wave Function Collapse model. Only the largest connected region is kept.
+pub struct WaveFunctionCollapseBuilder {
+    width: Int,
+    height: Int,
+    settings: WfcSettings,
+}
+
+impl WaveFunctionCollapseBuilder {
+    pub fn new(width: Int, height: Int) -> Self {
+        Self::with_settings(width, height, WfcSettings::default())
+    }
+
+    pub fn with_settings(width: Int, height: Int, settings: WfcSettings) -> Self {
+        Self {
+            width,
+            height,
+            settings,
+        }
+    }
+
+    fn attempt(&self, patterns: &Patterns, rng: &mut RandomNumberGenerator) -> Option<AreaGrid> {
+        let mut area = collapse(
+            patterns,
+            self.width,
+            self.height,
+            self.settings.border_walls,
+            rng,
+        )?;
+        keep_largest_region(&mut area);
+
+        let min_floor = (self.width * self.height) as Real * MIN_FLOOR_RATIO;
+        if (floor_positions(&area).len() as Real) < min_floor {
+            return None;
+        }
+        Some(area)
+    }
+}
+
+impl MapBuilder for WaveFunctionCollapseBuilder {
+    fn build(&mut self, rng: &mut RandomNumberGenerator) -> BuiltMap {
+        let samples: Vec<Sample> = self.settings.samples.iter().map(Sample::load).collect();
+        let patterns = Patterns::learn(&samples, self.settings.pattern_size);
+
+        let area = (0..MAX_ATTEMPTS).find_map(|_| self.attempt(&patterns, rng));
+        let area = match area {
+            Some(area) => area,
+            // The samples couldn't tile this area, so still hand back a playable map.
+            None => return CellularAutomataBuilder::new(self.width, self.height).build(rng),
+        };
+
+        let player_start = central_floor(&area);
+        let spawn_points = scatter_spawn_points(&area, &player_start, SPAWN_COUNT, rng);
+
+        BuiltMap {
+            area,
+            player_start,
+            spawn_points,
+            exit: None,
+            doors: vec![],
+            markers: vec![],
+            metadata: MapMetadata {
+                name: "Wave Function Collapse".to_string(),
+                seed: 0,
+                rooms: vec![],
+            },
+        }
+    }
+}
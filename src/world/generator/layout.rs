@@ -0,0 +1,107 @@
+use std::fs::File;
+
+use rltk::rex::XpFile;
+use serde::Deserialize;
+
+use crate::{
+    core::types::{Index, Int},
+    util::helpers::cp437,
+};
+
+/// Where a hand-drawn layout is read from.
+#[derive(Debug, Clone, Deserialize)]
+pub enum LayoutSource {
+    /// Rows of characters, written like the layouts in `src/test/data/maps.ron`.
+    Ascii(String),
+    /// Path to a REXPaint file. Only the first layer is read, and full blocks count as `#`.
+    Rex(String),
+}
+
+/// A rectangle of layout characters, read row by row.
+#[derive(Debug, Clone)]
+pub struct CharGrid {
+    pub width: Int,
+    pub height: Int,
+    chars: Vec<char>,
+}
+
+impl CharGrid {
+    pub fn load(source: &LayoutSource) -> Self {
+        match source {
+            LayoutSource::Ascii(layout) => Self::from_ascii(layout),
+            LayoutSource::Rex(path) => Self::from_rex(path),
+        }
+    }
+
+    pub fn from_ascii(layout: &str) -> Self {
+        let rows: Vec<&str> = layout
+            .split('\n')
+            .map(|row| row.trim())
+            .filter(|row| !row.is_empty())
+            .collect();
+        let width = rows.first().map_or(0, |row| row.chars().count()) as Int;
+
+        let mut chars = vec![];
+        for row in &rows {
+            assert_eq!(
+                width,
+                row.chars().count() as Int,
+                "Uneven layout row: {:?}",
+                row
+            );
+            chars.extend(row.chars());
+        }
+
+        Self {
+            width,
+            height: rows.len() as Int,
+            chars,
+        }
+    }
+
+    pub fn from_rex(path: &str) -> Self {
+        let mut file = File::open(path).expect("Failed opening REXPaint layout");
+        let xp = XpFile::read(&mut file).expect("Failed reading REXPaint layout");
+        let layer = &xp.layers[0];
+
+        let mut chars = vec![];
+        for y in 0..layer.height {
+            for x in 0..layer.width {
+                let c = match layer.get(x, y) {
+                    Some(cell) if cell.ch == cp437('█') => '#',
+                    Some(cell) => char::from_u32(cell.ch).unwrap_or('#'),
+                    None => '#',
+                };
+                chars.push(c);
+            }
+        }
+
+        Self {
+            width: layer.width as Int,
+            height: layer.height as Int,
+            chars,
+        }
+    }
+
+    pub fn get(&self, x: Int, y: Int) -> char {
+        self.chars[(y * self.width + x) as Index]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::CharGrid;
+
+    #[test]
+    fn reads_ascii_rows() {
+        let grid = CharGrid::from_ascii(
+            "####
+             #.g#
+             ####",
+        );
+        assert_eq!(4, grid.width);
+        assert_eq!(3, grid.height);
+        assert_eq!('#', grid.get(0, 1));
+        assert_eq!('g', grid.get(2, 1));
+    }
+}
@@ -1,165 +1,389 @@
-use std::cmp::{max, min};
-
 use bevy::prelude::*;
 use rltk::RandomNumberGenerator;
 
 use crate::{
-    actors::{Action, Activity, ActorBundle, Player, WeaponBundle},
-    ai::Monster,
+    actors::{
+        compute_next_position, effects::HazardDefinitions, Action, Activity, Actor, ActorBundle,
+        Experience, Player, Weapon, WeaponBundle,
+    },
+    ai::{
+        Awareness, Faction, Hearing, Monster, MonsterDefinition, MonsterDefinitions, Squad,
+        SquadMember, UtilityAI,
+    },
     core::{
-        types::{GridPos, Index, Int},
+        types::{Cardinal, Direction, GridPos, Int},
         MainPointOfView,
     },
-    world::{AreaGrid, Renderable, TileType, Viewshed, WorldMap},
+    util::algorithms::{field_of_view::FOV, geometry::chessboard_neighbours},
+    world::{
+        Darkvision, Dungeon, Item, Keyring, LightSource, OpensDoors, Renderable, TileType,
+        Viewshed, WorldMap,
+    },
 };
 
-use super::types::Room;
+use super::{
+    builders::{build_map, open_seam, BuiltMap, GeneratorSettings},
+    post_processing::place_exit,
+    prefab::Marker,
+};
+
+const PLAYER_FACTION: &str = "player";
+/// Walking distance between the stairs when the settings don't place an exit.
+const MIN_STAIRS_DISTANCE: Int = 20;
+/// Packs grow by one monster every this many levels.
+const PACK_GROWTH_DEPTHS: Int = 2;
+const TORCH_RADIUS: Int = 6;
 
-struct TempMap {
-    tiles: Vec<TileType>,
-    dimensions: IVec2,
+pub fn generate_map_system(
+    mut commands: Commands,
+    mut world_map: ResMut<WorldMap>,
+    mut dungeon: ResMut<Dungeon>,
+    settings: Res<GeneratorSettings>,
+    monster_definitions: Res<MonsterDefinitions>,
+    hazard_definitions: Res<HazardDefinitions>,
+) {
+    dungeon.seed = settings
+        .seed
+        .unwrap_or_else(|| RandomNumberGenerator::new().next_u64());
+    let player_start = generate_level(
+        &mut commands,
+        &mut world_map,
+        &settings,
+        &monster_definitions,
+        &hazard_definitions,
+        dungeon.depth,
+        dungeon.level_seed(dungeon.depth),
+    );
+
+    let (player_x, player_y) = (player_start.x, player_start.y);
+
+    commands
+        .spawn()
+        .insert(Player)
+        .insert(Faction(PLAYER_FACTION.to_string()))
+        .insert(MainPointOfView)
+        .insert(OpensDoors)
+        .insert(Keyring::default())
+        .insert(Experience::default())
+        .insert(LightSource {
+            radius: TORCH_RADIUS,
+            color: (1.0, 0.75, 0.4),
+            intensity: 1.0,
+            falloff: 1.5,
+            lit: true,
+        })
+        .insert(Activity {
+            action: Action::Wait,
+            time_to_complete: 0,
+        })
+        .insert_bundle(ActorBundle {
+            position: GridPos(IVec2::new(player_x, player_y)),
+            viewshed: Viewshed::with_fov(FOV::default()),
+            renderable: Renderable {
+                glyph: '@',
+                fg: Color::CYAN,
+                bg: Color::BLACK,
+            },
+            ..Default::default()
+        })
+        .with_children(|actor| {
+            actor.spawn_bundle(WeaponBundle {
+                position: GridPos(IVec2::new(player_x, player_y - 1)),
+                ..Default::default()
+            });
+        });
 }
 
-impl TempMap {
-    pub fn xy_idx(&self, x: Int, y: Int) -> Index {
-        ((y * self.dimensions.y) + x) as Index
+/// Builds the level at `depth` into `world_map` and spawns its inhabitants.
+/// Returns where a player arriving on the level starts.
+pub fn generate_level(
+    commands: &mut Commands,
+    world_map: &mut WorldMap,
+    settings: &GeneratorSettings,
+    monster_definitions: &MonsterDefinitions,
+    hazard_definitions: &HazardDefinitions,
+    depth: Int,
+    seed: u64,
+) -> IVec2 {
+    let maps = build_level(settings, depth, seed);
+    let player_start = maps[0].player_start;
+
+    for (index, built) in maps.into_iter().enumerate() {
+        let offset = IVec2::new(index as Int * settings.width, 0);
+        info!(
+            "Generated {} with seed {} at {} on depth {}",
+            built.metadata.name, built.metadata.seed, offset, depth
+        );
+        populate(
+            commands,
+            &built,
+            &offset,
+            monster_definitions,
+            hazard_definitions,
+            depth,
+        );
+        world_map.insert_offset(&offset, built.area);
     }
+    player_start
 }
 
-pub struct MapGenerator {}
+/// The areas of one level, with stairs down at the exit of the first area and,
+/// below the top level, stairs up where the player arrives.
+pub fn build_level(settings: &GeneratorSettings, depth: Int, seed: u64) -> Vec<BuiltMap> {
+    let mut maps = build_areas(&GeneratorSettings {
+        builder: settings.builder_at(depth),
+        seed: Some(seed),
+        ..settings.clone()
+    });
 
-impl MapGenerator {
-    fn apply_room_to_map(&self, map: &mut TempMap, room: &Room) {
-        for y in room.y1 + 1..=room.y2 {
-            for x in room.x1 + 1..=room.x2 {
-                let idx = map.xy_idx(x, y);
-                map.tiles[idx] = TileType::Floor;
-            }
-        }
+    let first = &mut maps[0];
+    let mut rng = RandomNumberGenerator::seeded(seed);
+    let down_stairs = first.exit.or_else(|| {
+        place_exit(
+            &first.area,
+            &first.player_start,
+            MIN_STAIRS_DISTANCE,
+            &mut rng,
+        )
+    });
+    if let Some(down_stairs) = down_stairs {
+        first.area.set_tile(&down_stairs, TileType::DOWN_STAIRS);
     }
-
-    fn apply_horizontal_tunnel(&self, map: &mut TempMap, x1: Int, x2: Int, y: Int) {
-        for x in min(x1, x2)..=max(x1, x2) {
-            let idx = map.xy_idx(x, y);
-            map.tiles[idx] = TileType::Floor;
-        }
+    if depth > 0 {
+        first
+            .area
+            .set_tile(&first.player_start, TileType::UP_STAIRS);
+    }
+    for built in &mut maps {
+        hang_doors(built);
+        built.area.ambient_light = settings.ambient_light_at(depth);
     }
+    maps
+}
 
-    fn apply_vertical_tunnel(&self, map: &mut TempMap, y1: Int, y2: Int, x: Int) {
-        for y in min(y1, y2)..=max(y1, y2) {
-            let idx = map.xy_idx(x, y);
-            map.tiles[idx] = TileType::Floor;
+/// Closes off every door position the builder left, keeping monsters from spawning in them.
+fn hang_doors(built: &mut BuiltMap) {
+    for door in &built.doors {
+        if built.area.tile_at(door).map(|tile| tile.which()) == Some(TileType::FLOOR) {
+            built.area.set_tile(door, TileType::CLOSED_DOOR);
         }
     }
+    let doors = &built.doors;
+    built.spawn_points.retain(|pos| !doors.contains(pos));
+}
 
-    pub fn generate_new_map(&self) -> (TempMap, Vec<Room>) {
-        let mut map = TempMap {
-            tiles: vec![TileType::Wall; 80 * 50],
-            dimensions: IVec2::new(50, 80),
-        };
-        let mut rooms: Vec<Room> = Vec::new();
-
-        const MAX_ROOMS: Int = 30;
-        const MIN_SIZE: Int = 6;
-        const MAX_SIZE: Int = 10;
-
-        let mut rng = RandomNumberGenerator::new();
-
-        // for _ in 0..MAX_ROOMS {
-        //     let w = rng.range(MIN_SIZE, MAX_SIZE);
-        //     let h = rng.range(MIN_SIZE, MAX_SIZE);
-        //     let x = rng.roll_dice(1, 80 - w - 1) - 1;
-        //     let y = rng.roll_dice(1, 50 - h - 1) - 1;
-        //     let new_room = Room::new(x, y, w, h);
-        //     if !rooms
-        //         .iter()
-        //         .any(|other_room| new_room.intersect(other_room))
-        //     {
-        //         self.apply_room_to_map(&mut map, &new_room);
-        //         if !rooms.is_empty() {
-        //             let (new_x, new_y) = new_room.center();
-        //             let (prev_x, prev_y) = rooms[rooms.len() - 1].center();
-
-        //             if 1 == rng.range(0, 2) {
-        //                 self.apply_horizontal_tunnel(&mut map, prev_x, new_x, prev_y);
-        //                 self.apply_vertical_tunnel(&mut map, prev_y, new_y, new_x);
-        //             } else {
-        //                 self.apply_vertical_tunnel(&mut map, prev_y, new_y, prev_x);
-        //                 self.apply_horizontal_tunnel(&mut map, prev_x, new_x, new_y);
-        //             }
-        //         }
-
-        //         rooms.push(new_room);
-        //     }
-        // }
-        let new_room = Room::new(0, 0, 78, 48);
-        self.apply_room_to_map(&mut map, &new_room);
-        rooms.push(new_room);
-        (map, rooms)
+/// The first area plus `extra_areas` more laid out eastward, each one opened up to
+/// the one before it so actors can walk across.
+fn build_areas(settings: &GeneratorSettings) -> Vec<BuiltMap> {
+    let mut maps = vec![build_map(settings)];
+    let first_seed = maps[0].metadata.seed;
+
+    for index in 1..=settings.extra_areas {
+        let mut next = build_map(&GeneratorSettings {
+            seed: Some(first_seed.wrapping_add(index as u64)),
+            ..settings.clone()
+        });
+        let mut rng = RandomNumberGenerator::seeded(next.metadata.seed);
+        let previous = maps.last_mut().unwrap();
+        if open_seam(&mut previous.area, &mut next.area, &mut rng).is_none() {
+            warn!("No shared row to join area {} to its neighbour", index);
+        }
+        maps.push(next);
     }
+    maps
 }
 
-pub fn generate_map_system(mut commands: Commands, mut world_map: ResMut<WorldMap>) {
-    let (new_map, rooms) = MapGenerator {}.generate_new_map();
-    world_map.insert_offset(
-        &IVec2::ZERO,
-        AreaGrid::from_tiles(&IVec2::new(50, 80), new_map.tiles),
-    );
-
-    let mut rng = rltk::RandomNumberGenerator::new();
-    for (_i, room) in rooms.iter().skip(1).enumerate() {
-        let (x, y) = room.center();
+/// Spawns the monsters and prefab markers of one area, shifted to its place in the world.
+/// Deeper levels allow tougher monsters and bring bigger packs.
+fn populate(
+    commands: &mut Commands,
+    built: &BuiltMap,
+    offset: &IVec2,
+    monster_definitions: &MonsterDefinitions,
+    hazard_definitions: &HazardDefinitions,
+    depth: Int,
+) {
+    let eligible: Vec<&MonsterDefinition> = monster_definitions
+        .monsters
+        .iter()
+        .filter(|definition| definition.min_depth <= depth)
+        .collect();
 
-        let roll = rng.roll_dice(1, 2);
-        let glyph = match roll {
-            1 => 'g',
-            _ => 'o',
+    let mut rng = RandomNumberGenerator::seeded(built.metadata.seed);
+    for spawn_point in &built.spawn_points {
+        let definition = match rng.random_slice_entry(&eligible) {
+            Some(definition) => *definition,
+            None => break,
         };
 
-        commands
-            .spawn()
-            .insert(Monster {})
-            .insert_bundle(ActorBundle {
-                position: GridPos(IVec2::new(x, y)),
-                viewshed: Viewshed::with_range(8),
-                renderable: Renderable {
-                    glyph,
-                    fg: Color::RED,
-                    bg: Color::BLACK,
-                },
-                ..Default::default()
-            })
-            .with_children(|actor| {
-                actor.spawn_bundle(WeaponBundle {
-                    position: GridPos(IVec2::new(x, y - 1)),
-                    ..Default::default()
-                });
-            });
+        if definition.pack_size > 1 {
+            let squad = commands.spawn().insert(Squad::default()).id();
+            let pack_size = definition.pack_size + depth / PACK_GROWTH_DEPTHS;
+            let positions = std::iter::once(*spawn_point).chain(
+                chessboard_neighbours(spawn_point).filter(|pos| !built.area.is_blocking(pos)),
+            );
+            for pos in positions.take(pack_size as usize) {
+                let monster = spawn_monster(commands, definition, *offset + pos, Cardinal::North);
+                commands.entity(monster).insert(SquadMember::of(squad));
+            }
+        } else {
+            spawn_monster(
+                commands,
+                definition,
+                *offset + *spawn_point,
+                Cardinal::North,
+            );
+        }
     }
 
-    let (player_x, player_y) = rooms[0].center();
-    commands
-        .spawn()
-        .insert(Player)
-        .insert(MainPointOfView)
-        .insert(Activity {
-            action: Action::Wait,
-            time_to_complete: 0,
+    for (pos, marker) in &built.markers {
+        let pos = *offset + *pos;
+        match marker {
+            Marker::Monster(kind) => match monster_definitions.get(kind) {
+                Some(definition) => {
+                    spawn_monster(commands, definition, pos, Cardinal::North);
+                }
+                None => warn!("Prefab asked for unknown monster {}", kind),
+            },
+            Marker::Hazard(kind) => match hazard_definitions.get(kind) {
+                Some(definition) => {
+                    commands.spawn_bundle(definition.bundle(pos));
+                }
+                None => warn!("Prefab asked for unknown hazard {}", kind),
+            },
+            Marker::Item { kind, glyph } => {
+                commands
+                    .spawn()
+                    .insert(Item { kind: kind.clone() })
+                    .insert(GridPos(pos))
+                    .insert(Renderable {
+                        glyph: *glyph,
+                        fg: Color::GOLD,
+                        bg: Color::BLACK,
+                    });
+            }
+        }
+    }
+}
+
+/// Spawns a monster facing `facing`, holding its weapon out in front.
+pub fn spawn_monster(
+    commands: &mut Commands,
+    definition: &MonsterDefinition,
+    pos: IVec2,
+    facing: Cardinal,
+) -> Entity {
+    let mut monster = commands.spawn();
+    if let Some(weights) = definition.utility {
+        monster.insert(UtilityAI { weights });
+    }
+    if definition.opens_doors {
+        monster.insert(OpensDoors);
+    }
+    if definition.darkvision > 0 {
+        monster.insert(Darkvision {
+            range: definition.darkvision,
+        });
+    }
+    if let Some(inflicts) = definition.inflicts {
+        monster.insert(inflicts);
+    }
+    monster
+        .insert(Monster {
+            kind: definition.kind.clone(),
         })
+        .insert(Faction(definition.faction.clone()))
+        .insert(Hearing::with_threshold(definition.hearing_threshold))
+        .insert(Awareness::default())
         .insert_bundle(ActorBundle {
-            position: GridPos(IVec2::new(player_x, player_y)),
-            viewshed: Viewshed::with_range(1),
+            actor: Actor {
+                facing,
+                ..Default::default()
+            },
+            position: GridPos(pos),
+            viewshed: Viewshed::with_fov(definition.fov.clone()),
             renderable: Renderable {
-                glyph: '@',
-                fg: Color::CYAN,
+                glyph: definition.glyph,
+                fg: definition.fg(),
                 bg: Color::BLACK,
             },
             ..Default::default()
         })
         .with_children(|actor| {
             actor.spawn_bundle(WeaponBundle {
-                position: GridPos(IVec2::new(player_x, player_y - 1)),
+                weapon: Weapon {
+                    knockback: definition.knockback,
+                    ..Default::default()
+                },
+                position: GridPos(compute_next_position(Direction::Forward, facing, &pos)),
                 ..Default::default()
             });
-        });
+        })
+        .id()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::world::{
+        generator::{
+            builders::{BuilderKind, GeneratorSettings},
+            post_processing::PostProcess,
+        },
+        TileType,
+    };
+
+    use super::build_level;
+
+    fn settings() -> GeneratorSettings {
+        GeneratorSettings {
+            builder: BuilderKind::RoomsAndCorridors,
+            seed: None,
+            width: 80,
+            height: 50,
+            post_processing: vec![
+                PostProcess::CullUnreachable,
+                PostProcess::PlaceDoors,
+                PostProcess::PlaceStartAndExit(20),
+            ],
+            extra_areas: 1,
+            depth_builders: vec![],
+            ambient_light: vec![],
+        }
+    }
+
+    fn count(tiles: &[TileType], tile_type: TileType) -> usize {
+        tiles.iter().filter(|tile| **tile == tile_type).count()
+    }
+
+    #[test]
+    fn top_level_only_goes_down() {
+        let maps = build_level(&settings(), 0, 4);
+        assert_eq!(2, maps.len());
+        assert_eq!(1, count(&maps[0].area.tiles, TileType::DOWN_STAIRS));
+        assert_eq!(0, count(&maps[0].area.tiles, TileType::UP_STAIRS));
+    }
+
+    #[test]
+    fn deeper_levels_arrive_on_stairs_up() {
+        let maps = build_level(&settings(), 3, 4);
+        let start = maps[0].player_start;
+        assert_eq!(
+            TileType::UP_STAIRS,
+            maps[0].area.tile_at(&start).unwrap().which()
+        );
+        assert_eq!(1, count(&maps[0].area.tiles, TileType::DOWN_STAIRS));
+    }
+
+    #[test]
+    fn doors_are_hung_closed() {
+        let maps = build_level(&settings(), 0, 4);
+        assert!(!maps[0].doors.is_empty());
+        for door in &maps[0].doors {
+            assert_eq!(
+                TileType::CLOSED_DOOR,
+                maps[0].area.tile_at(door).unwrap().which()
+            );
+            assert!(!maps[0].spawn_points.contains(door));
+        }
+    }
 }
@@ -1,5 +1,10 @@
+pub mod builders;
+pub mod layout;
 mod map_generator;
+pub mod post_processing;
+pub mod prefab;
 mod rect;
 mod types;
+pub mod wfc;
 
 pub use map_generator::*;
@@ -0,0 +1,234 @@
+use std::collections::HashMap;
+
+use bevy::math::IVec2;
+use rltk::RandomNumberGenerator;
+use serde::Deserialize;
+
+use crate::{
+    core::types::Int,
+    util::algorithms::pathfinding::dijkstra_map,
+    world::{AreaGrid, TileType},
+};
+
+use super::{
+    builders::{central_floor, floor_positions, reachable_from, BuiltMap},
+    prefab::{place_prefabs, Prefabs},
+    types::Room,
+};
+
+/// A step applied to a freshly built map, in the order listed in the generator settings.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+pub enum PostProcess {
+    /// Walls off every floor tile the player cannot walk to.
+    CullUnreachable,
+    /// Fills this many tiles along the area edges with walls.
+    ThickenBorders(Int),
+    /// Puts doors where corridors enter rooms.
+    PlaceDoors,
+    /// Stamps up to this many prefabs from `prefabs.ron` onto open floor.
+    PlacePrefabs(Int),
+    /// Makes sure the start is walkable and puts the exit at least this many steps away.
+    PlaceStartAndExit(Int),
+}
+
+impl PostProcess {
+    pub fn apply(&self, built: &mut BuiltMap, rng: &mut RandomNumberGenerator) {
+        match self {
+            PostProcess::CullUnreachable => {
+                ensure_walkable_start(built);
+                cull_unreachable(&mut built.area, &built.player_start);
+            }
+            PostProcess::ThickenBorders(thickness) => thicken_borders(&mut built.area, *thickness),
+            PostProcess::PlaceDoors => {
+                built.doors = door_positions(&built.area, &built.metadata.rooms);
+            }
+            PostProcess::PlacePrefabs(count) => {
+                place_prefabs(built, &Prefabs::default().prefabs, *count, rng);
+            }
+            PostProcess::PlaceStartAndExit(min_distance) => {
+                ensure_walkable_start(built);
+                built.exit = place_exit(&built.area, &built.player_start, *min_distance, rng);
+            }
+        }
+
+        let area = &built.area;
+        built.spawn_points.retain(|pos| !area.is_blocking(pos));
+        built.doors.retain(|pos| !area.is_blocking(pos));
+        built.markers.retain(|(pos, _)| !area.is_blocking(pos));
+    }
+}
+
+fn ensure_walkable_start(built: &mut BuiltMap) {
+    if built.area.is_blocking(&built.player_start) {
+        built.player_start = central_floor(&built.area);
+    }
+}
+
+pub fn cull_unreachable(area: &mut AreaGrid, from: &IVec2) {
+    let reachable = reachable_from(area, from);
+    for pos in floor_positions(area) {
+        if !reachable.contains(&pos) {
+            area.set_tile(&pos, TileType::WALL);
+        }
+    }
+}
+
+pub fn thicken_borders(area: &mut AreaGrid, thickness: Int) {
+    for pos in area.clone() {
+        let distance_to_edge = pos
+            .x
+            .min(pos.y)
+            .min(area.width - 1 - pos.x)
+            .min(area.height - 1 - pos.y);
+        if distance_to_edge < thickness {
+            area.set_tile(&pos, TileType::WALL);
+        }
+    }
+}
+
+/// Walking distance from `start` to every reachable tile.
+pub fn walking_distances(area: &AreaGrid, start: &IVec2) -> HashMap<IVec2, Int> {
+    dijkstra_map(&[*start], Int::MAX, &|pos| {
+        if area.is_point_in_bounds(pos) && !area.is_blocking(pos) {
+            Some(1)
+        } else {
+            None
+        }
+    })
+}
+
+/// A random tile at least `min_distance` steps away from the start, or the farthest
+/// one when the map is too small for that.
+pub fn place_exit(
+    area: &AreaGrid,
+    start: &IVec2,
+    min_distance: Int,
+    rng: &mut RandomNumberGenerator,
+) -> Option<IVec2> {
+    let mut distances: Vec<(IVec2, Int)> = walking_distances(area, start).into_iter().collect();
+    distances.sort_by_key(|(pos, distance)| (*distance, pos.y, pos.x));
+
+    let far_enough: Vec<IVec2> = distances
+        .iter()
+        .filter(|(_, distance)| *distance >= min_distance)
+        .map(|(pos, _)| *pos)
+        .collect();
+
+    match rng.random_slice_entry(&far_enough) {
+        Some(exit) => Some(*exit),
+        None => distances
+            .last()
+            .map(|(pos, _)| *pos)
+            .filter(|pos| pos != start),
+    }
+}
+
+/// Gaps in room walls only one tile wide, which is where corridors cut through.
+pub fn door_positions(area: &AreaGrid, rooms: &[Room]) -> Vec<IVec2> {
+    let is_wall = |pos: IVec2| area.is_blocking(&pos);
+    let mut doors: Vec<IVec2> = vec![];
+
+    for room in rooms {
+        let (left, right) = (room.x1, room.x2 + 1);
+        let (top, bottom) = (room.y1, room.y2 + 1);
+
+        let vertical_sides = (top + 1..bottom)
+            .flat_map(|y| [IVec2::new(left, y), IVec2::new(right, y)])
+            .filter(|pos| is_wall(*pos + IVec2::Y) && is_wall(*pos - IVec2::Y));
+        let horizontal_sides = (left + 1..right)
+            .flat_map(|x| [IVec2::new(x, top), IVec2::new(x, bottom)])
+            .filter(|pos| is_wall(*pos + IVec2::X) && is_wall(*pos - IVec2::X));
+
+        for pos in vertical_sides.chain(horizontal_sides) {
+            if area.is_point_in_bounds(&pos) && !area.is_blocking(&pos) && !doors.contains(&pos) {
+                doors.push(pos);
+            }
+        }
+    }
+
+    doors
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::math::IVec2;
+    use rltk::RandomNumberGenerator;
+
+    use crate::{
+        test::helpers::visibility::from_ascii_layout,
+        world::{generator::types::Room, TileType},
+    };
+
+    use super::{cull_unreachable, door_positions, place_exit, thicken_borders};
+
+    #[test]
+    fn culls_regions_cut_off_from_start() {
+        let (start, mut area) = from_ascii_layout(
+            "#######
+             #@.#..#
+             #######",
+        );
+        cull_unreachable(&mut area, &start);
+
+        assert_eq!(
+            TileType::FLOOR,
+            area.tile_at(&IVec2::new(2, 1)).unwrap().which()
+        );
+        assert_eq!(
+            TileType::WALL,
+            area.tile_at(&IVec2::new(4, 1)).unwrap().which()
+        );
+        assert_eq!(
+            TileType::WALL,
+            area.tile_at(&IVec2::new(5, 1)).unwrap().which()
+        );
+    }
+
+    #[test]
+    fn exit_is_far_enough() {
+        let (start, area) = from_ascii_layout(
+            "##########
+             #@.......#
+             ##########",
+        );
+        let mut rng = RandomNumberGenerator::seeded(5);
+        for _ in 0..10 {
+            let exit = place_exit(&area, &start, 6, &mut rng).unwrap();
+            assert!(exit.x >= 7);
+        }
+
+        let exit = place_exit(&area, &start, 100, &mut rng);
+        assert_eq!(Some(IVec2::new(8, 1)), exit);
+    }
+
+    #[test]
+    fn borders_get_thicker() {
+        let (_, mut area) = from_ascii_layout(
+            "#######
+             #.....#
+             #..@..#
+             #.....#
+             #######",
+        );
+        thicken_borders(&mut area, 2);
+
+        assert!(area.is_blocking(&IVec2::new(1, 1)));
+        assert!(area.is_blocking(&IVec2::new(5, 2)));
+        assert!(!area.is_blocking(&IVec2::new(3, 2)));
+    }
+
+    #[test]
+    fn doors_where_corridors_meet_rooms() {
+        let (_, area) = from_ascii_layout(
+            "##########
+             #...######
+             #...@....#
+             #...######
+             ##########",
+        );
+        let room = Room::new(0, 0, 3, 3);
+        let doors = door_positions(&area, &[room]);
+
+        assert_eq!(vec![IVec2::new(4, 2)], doors);
+    }
+}
@@ -0,0 +1,372 @@
+use std::collections::HashMap;
+
+use bevy::math::IVec2;
+use rltk::RandomNumberGenerator;
+use serde::Deserialize;
+
+use crate::{
+    core::types::Int,
+    util::{algorithms::geometry::chessboard_rotate, helpers::deserialize},
+    world::TileType,
+};
+
+use super::{
+    builders::{reachable_from, BuiltMap},
+    layout::{CharGrid, LayoutSource},
+};
+
+/// Tries per prefab before giving up on finding room for it.
+const PLACEMENT_TRIES: Int = 40;
+
+/// Something to spawn on a prefab tile once the map is in play.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub enum Marker {
+    /// A monster, by its kind in `monsters.ron`.
+    Monster(String),
+    Item {
+        kind: String,
+        glyph: char,
+    },
+    /// A hazard, by its kind in `hazards.ron`.
+    Hazard(String),
+}
+
+/// A hand-authored set piece. `#` is wall, `.` is floor, `tiles` names any other
+/// tile by its definition, and every legend character is a floor tile carrying that marker.
+#[derive(Debug, Clone, Deserialize)]
+pub struct Prefab {
+    pub name: String,
+    pub layout: LayoutSource,
+    #[serde(default)]
+    pub tiles: HashMap<char, String>,
+    #[serde(default)]
+    pub legend: HashMap<char, Marker>,
+    /// Allows quarter turns when placing.
+    #[serde(default)]
+    pub rotate: bool,
+    /// Allows flipping left to right when placing.
+    #[serde(default)]
+    pub mirror: bool,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Prefabs {
+    pub prefabs: Vec<Prefab>,
+}
+
+impl Default for Prefabs {
+    fn default() -> Self {
+        deserialize("assets/data/prefabs.ron")
+    }
+}
+
+/// A prefab in one orientation, with offsets starting at zero.
+#[derive(Debug, Clone)]
+pub struct PrefabShape {
+    pub size: IVec2,
+    pub tiles: Vec<(IVec2, TileType)>,
+    pub markers: Vec<(IVec2, Marker)>,
+}
+
+impl PrefabShape {
+    fn transformed(&self, octants: Int, mirrored: bool) -> Self {
+        let transform = |pos: &IVec2| {
+            let pos = if mirrored {
+                IVec2::new(-pos.x, pos.y)
+            } else {
+                *pos
+            };
+            chessboard_rotate(&pos, octants)
+        };
+        let corner = self
+            .tiles
+            .iter()
+            .map(|(pos, _)| transform(pos))
+            .fold(IVec2::new(Int::MAX, Int::MAX), |corner, pos| {
+                corner.min(pos)
+            });
+
+        let tiles: Vec<(IVec2, TileType)> = self
+            .tiles
+            .iter()
+            .map(|(pos, tile)| (transform(pos) - corner, *tile))
+            .collect();
+        let markers = self
+            .markers
+            .iter()
+            .map(|(pos, marker)| (transform(pos) - corner, marker.clone()))
+            .collect();
+        let size = tiles
+            .iter()
+            .fold(IVec2::ZERO, |size, (pos, _)| size.max(*pos + IVec2::ONE));
+
+        Self {
+            size,
+            tiles,
+            markers,
+        }
+    }
+}
+
+impl Prefab {
+    pub fn shape(&self) -> PrefabShape {
+        let grid = CharGrid::load(&self.layout);
+        let mut tiles = vec![];
+        let mut markers = vec![];
+
+        for y in 0..grid.height {
+            for x in 0..grid.width {
+                let pos = IVec2::new(x, y);
+                match grid.get(x, y) {
+                    '#' => tiles.push((pos, TileType::WALL)),
+                    '.' => tiles.push((pos, TileType::FLOOR)),
+                    c if self.tiles.contains_key(&c) => {
+                        let name = &self.tiles[&c];
+                        let tile = TileType::named(name).unwrap_or_else(|| {
+                            panic!("Prefab {} uses unknown tile {}", self.name, name)
+                        });
+                        tiles.push((pos, tile));
+                    }
+                    c => match self.legend.get(&c) {
+                        Some(marker) => {
+                            tiles.push((pos, TileType::FLOOR));
+                            markers.push((pos, marker.clone()));
+                        }
+                        None => panic!("Prefab {} has no legend for {:?}", self.name, c),
+                    },
+                }
+            }
+        }
+
+        PrefabShape {
+            size: IVec2::new(grid.width, grid.height),
+            tiles,
+            markers,
+        }
+    }
+
+    /// Every orientation the prefab allows, always starting with the one as drawn.
+    pub fn orientations(&self) -> Vec<PrefabShape> {
+        let shape = self.shape();
+        let turns: &[Int] = if self.rotate { &[0, 2, 4, 6] } else { &[0] };
+        let flips: &[bool] = if self.mirror {
+            &[false, true]
+        } else {
+            &[false]
+        };
+
+        flips
+            .iter()
+            .flat_map(|mirrored| turns.iter().map(move |octants| (*octants, *mirrored)))
+            .map(|(octants, mirrored)| shape.transformed(octants, mirrored))
+            .collect()
+    }
+}
+
+/// Stamps up to `count` random prefabs onto open floor. A prefab only goes where
+/// its whole footprint is reachable floor clear of the start, exit and doors, and
+/// it is taken back out if its walls would cut any part of the map off.
+pub fn place_prefabs(
+    built: &mut BuiltMap,
+    prefabs: &[Prefab],
+    count: Int,
+    rng: &mut RandomNumberGenerator,
+) {
+    let mut placed = 0;
+    for _ in 0..count * PLACEMENT_TRIES {
+        if placed >= count {
+            break;
+        }
+        let prefab = match rng.random_slice_entry(prefabs) {
+            Some(prefab) => prefab,
+            None => return,
+        };
+        let orientations = prefab.orientations();
+        let shape = rng.random_slice_entry(&orientations).unwrap();
+
+        let max_corner = IVec2::new(built.area.width, built.area.height) - shape.size;
+        if max_corner.x <= 1 || max_corner.y <= 1 {
+            continue;
+        }
+        let origin = IVec2::new(rng.range(1, max_corner.x), rng.range(1, max_corner.y));
+        if try_stamp(built, shape, &origin) {
+            placed += 1;
+        }
+    }
+}
+
+fn try_stamp(built: &mut BuiltMap, shape: &PrefabShape, origin: &IVec2) -> bool {
+    let reachable = reachable_from(&built.area, &built.player_start);
+    let footprint: Vec<IVec2> = shape.tiles.iter().map(|(pos, _)| *origin + *pos).collect();
+
+    let is_free = |pos: &IVec2| {
+        reachable.contains(pos)
+            && *pos != built.player_start
+            && Some(*pos) != built.exit
+            && !built.doors.contains(pos)
+            && !built
+                .markers
+                .iter()
+                .any(|(marker_pos, _)| marker_pos == pos)
+    };
+    if !footprint.iter().all(is_free) {
+        return false;
+    }
+
+    let previous = built.area.clone();
+    // Tiles the stamp takes out of the reachable floor. Doors still connect.
+    let mut blocked = 0;
+    for (pos, tile) in &shape.tiles {
+        if !tile.is_walkable() && !tile.is_door() {
+            blocked += 1;
+        }
+        built.area.set_tile(&(*origin + *pos), *tile);
+    }
+
+    if reachable_from(&built.area, &built.player_start).len() + blocked != reachable.len() {
+        built.area = previous;
+        return false;
+    }
+
+    built.spawn_points.retain(|pos| !footprint.contains(pos));
+    built.markers.extend(
+        shape
+            .markers
+            .iter()
+            .map(|(pos, marker)| (*origin + *pos, marker.clone())),
+    );
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use bevy::math::IVec2;
+    use rltk::RandomNumberGenerator;
+
+    use crate::{
+        test::helpers::visibility::from_ascii_layout,
+        world::{
+            generator::{
+                builders::{reachable_from, BuiltMap, MapMetadata},
+                layout::LayoutSource,
+            },
+            AreaGrid, TileType,
+        },
+    };
+
+    use super::{place_prefabs, try_stamp, Marker, Prefab, Prefabs};
+
+    fn shrine() -> Prefab {
+        Prefab {
+            name: "shrine".to_string(),
+            layout: LayoutSource::Ascii(
+                "###
+                 #g.
+                 ###"
+                .to_string(),
+            ),
+            tiles: HashMap::new(),
+            legend: HashMap::from([('g', Marker::Monster("goblin".to_string()))]),
+            rotate: true,
+            mirror: true,
+        }
+    }
+
+    #[test]
+    fn orientations_keep_the_marker_inside() {
+        let orientations = shrine().orientations();
+        assert_eq!(8, orientations.len());
+
+        for shape in &orientations {
+            let (marker_pos, _) = &shape.markers[0];
+            assert!(marker_pos.x >= 0 && marker_pos.y >= 0);
+            assert!(marker_pos.x < shape.size.x && marker_pos.y < shape.size.y);
+            let tile = shape.tiles.iter().find(|(pos, _)| pos == marker_pos);
+            assert_eq!(Some(TileType::FLOOR), tile.map(|(_, tile)| *tile));
+        }
+    }
+
+    #[test]
+    fn quarter_turn_swaps_size() {
+        let orientations = shrine().orientations();
+        assert_eq!(IVec2::new(3, 3), orientations[0].size);
+
+        let corridor = Prefab {
+            layout: LayoutSource::Ascii("#..#".to_string()),
+            ..shrine()
+        };
+        assert_eq!(IVec2::new(1, 4), corridor.orientations()[1].size);
+    }
+
+    fn open_map(start: IVec2, area: AreaGrid) -> BuiltMap {
+        BuiltMap {
+            area,
+            player_start: start,
+            spawn_points: vec![],
+            exit: None,
+            doors: vec![],
+            markers: vec![],
+            metadata: MapMetadata {
+                name: "Test".to_string(),
+                seed: 0,
+                rooms: vec![],
+            },
+        }
+    }
+
+    #[test]
+    fn stamped_prefabs_keep_map_connected() {
+        let (start, area) = from_ascii_layout(
+            "##############
+             #@...........#
+             #............#
+             #............#
+             #............#
+             #............#
+             ##############",
+        );
+        let mut built = open_map(start, area);
+        let mut rng = RandomNumberGenerator::seeded(3);
+        place_prefabs(&mut built, &[shrine()], 2, &mut rng);
+
+        assert!(!built.markers.is_empty());
+        let reachable = reachable_from(&built.area, &built.player_start);
+        for (pos, _) in &built.markers {
+            assert!(reachable.contains(pos));
+        }
+        for pos in built.area.clone() {
+            if !built.area.is_blocking(&pos) {
+                assert!(reachable.contains(&pos), "Cut off floor at {:?}", pos);
+            }
+        }
+    }
+
+    #[test]
+    fn every_prefab_fits_an_open_room() {
+        let (start, area) = from_ascii_layout(
+            "################
+             #@.............#
+             #..............#
+             #..............#
+             #..............#
+             #..............#
+             #..............#
+             #..............#
+             #..............#
+             #..............#
+             ################",
+        );
+        for prefab in Prefabs::default().prefabs {
+            for shape in prefab.orientations() {
+                let mut built = open_map(start, area.clone());
+                assert!(
+                    try_stamp(&mut built, &shape, &IVec2::new(4, 2)),
+                    "Could not place {}",
+                    prefab.name
+                );
+            }
+        }
+    }
+}
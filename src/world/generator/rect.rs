@@ -1,5 +1,6 @@
 use crate::core::types::Int;
 
+#[derive(Debug, Clone, Copy)]
 pub struct Rect {
     pub x1: Int,
     pub x2: Int,
@@ -0,0 +1,5 @@
+mod sample;
+pub use sample::*;
+
+mod solver;
+pub use solver::*;
@@ -0,0 +1,86 @@
+use serde::Deserialize;
+
+use crate::{
+    core::types::{Index, Int},
+    util::helpers::deserialize,
+    world::{
+        generator::layout::{CharGrid, LayoutSource},
+        TileType,
+    },
+};
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct WfcSettings {
+    /// Side of the square patterns learned from the samples.
+    pub pattern_size: Int,
+    /// Forces the outermost ring of the generated area to be walls.
+    pub border_walls: bool,
+    pub samples: Vec<LayoutSource>,
+}
+
+impl Default for WfcSettings {
+    fn default() -> Self {
+        deserialize("assets/data/wfc.ron")
+    }
+}
+
+/// A sample layout where `#` is wall and anything else is floor, so REXPaint
+/// samples can draw open ground with whatever glyph they like.
+#[derive(Debug, Clone)]
+pub struct Sample {
+    pub width: Int,
+    pub height: Int,
+    tiles: Vec<TileType>,
+}
+
+impl Sample {
+    pub fn load(source: &LayoutSource) -> Self {
+        Self::from_grid(&CharGrid::load(source))
+    }
+
+    pub fn from_ascii(layout: &str) -> Self {
+        Self::from_grid(&CharGrid::from_ascii(layout))
+    }
+
+    fn from_grid(grid: &CharGrid) -> Self {
+        let mut tiles = vec![];
+        for y in 0..grid.height {
+            for x in 0..grid.width {
+                tiles.push(match grid.get(x, y) {
+                    '#' => TileType::WALL,
+                    _ => TileType::FLOOR,
+                });
+            }
+        }
+
+        Self {
+            width: grid.width,
+            height: grid.height,
+            tiles,
+        }
+    }
+
+    pub fn tile(&self, x: Int, y: Int) -> TileType {
+        self.tiles[(y * self.width + x) as Index]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::world::TileType;
+
+    use super::Sample;
+
+    #[test]
+    fn parses_ascii_layout() {
+        let sample = Sample::from_ascii(
+            "####
+             #..#
+             ####",
+        );
+        assert_eq!(4, sample.width);
+        assert_eq!(3, sample.height);
+        assert_eq!(TileType::WALL, sample.tile(0, 1));
+        assert_eq!(TileType::FLOOR, sample.tile(2, 1));
+    }
+}
@@ -0,0 +1,375 @@
+use std::collections::HashMap;
+
+use bevy::math::IVec2;
+use rltk::RandomNumberGenerator;
+
+use crate::{
+    core::types::{Index, Int, Real},
+    world::{generator::builders::filled_area, AreaGrid, TileType},
+};
+
+use super::Sample;
+
+/// Right, down, left, up. The opposite of direction `d` is `(d + 2) % 4`.
+const OFFSETS: [(Int, Int); 4] = [(1, 0), (0, 1), (-1, 0), (0, -1)];
+
+fn opposite(direction: Index) -> Index {
+    (direction + 2) % 4
+}
+
+/// Every distinct square pattern found in the samples, how often it shows up,
+/// and which patterns may sit next to it.
+pub struct Patterns {
+    size: Int,
+    tiles: Vec<Vec<TileType>>,
+    weights: Vec<Real>,
+    /// `propagator[direction][pattern]` lists the patterns allowed one step away in that direction.
+    propagator: [Vec<Vec<Index>>; 4],
+}
+
+impl Patterns {
+    pub fn learn(samples: &[Sample], size: Int) -> Self {
+        let mut tiles: Vec<Vec<TileType>> = vec![];
+        let mut weights: Vec<Real> = vec![];
+        let mut indices: HashMap<Vec<TileType>, Index> = HashMap::new();
+
+        for sample in samples {
+            for y in 0..=sample.height - size {
+                for x in 0..=sample.width - size {
+                    let pattern: Vec<TileType> = (0..size * size)
+                        .map(|i| sample.tile(x + i % size, y + i / size))
+                        .collect();
+                    match indices.get(&pattern) {
+                        Some(index) => weights[*index] += 1.,
+                        None => {
+                            indices.insert(pattern.clone(), tiles.len());
+                            tiles.push(pattern);
+                            weights.push(1.);
+                        }
+                    }
+                }
+            }
+        }
+
+        let mut propagator: [Vec<Vec<Index>>; 4] = Default::default();
+        for (direction, (dx, dy)) in OFFSETS.iter().enumerate() {
+            propagator[direction] = tiles
+                .iter()
+                .map(|a| {
+                    (0..tiles.len())
+                        .filter(|b| agrees(a, &tiles[*b], *dx, *dy, size))
+                        .collect()
+                })
+                .collect();
+        }
+
+        Self {
+            size,
+            tiles,
+            weights,
+            propagator,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.tiles.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.tiles.is_empty()
+    }
+
+    fn tile(&self, pattern: Index, x: Int, y: Int) -> TileType {
+        self.tiles[pattern][(y * self.size + x) as Index]
+    }
+}
+
+/// Whether `b`, shifted by `(dx, dy)`, matches `a` wherever the two overlap.
+fn agrees(a: &[TileType], b: &[TileType], dx: Int, dy: Int, size: Int) -> bool {
+    for y in dy.max(0)..(size + dy).min(size) {
+        for x in dx.max(0)..(size + dx).min(size) {
+            if a[(y * size + x) as Index] != b[((y - dy) * size + (x - dx)) as Index] {
+                return false;
+            }
+        }
+    }
+    true
+}
+
+/// One cell per pattern position, each holding the patterns it could still collapse to.
+struct Wave<'a> {
+    patterns: &'a Patterns,
+    columns: Int,
+    rows: Int,
+    possible: Vec<Vec<bool>>,
+    /// How many neighbouring patterns still support a pattern, per cell and direction.
+    compatible: Vec<Vec<[Int; 4]>>,
+    counts: Vec<usize>,
+    weight_sums: Vec<Real>,
+    weight_log_sums: Vec<Real>,
+    banned: Vec<(Index, Index)>,
+}
+
+impl<'a> Wave<'a> {
+    fn new(patterns: &'a Patterns, columns: Int, rows: Int) -> Self {
+        let cell_count = (columns * rows) as Index;
+        let compatible: Vec<[Int; 4]> = (0..patterns.len())
+            .map(|pattern| {
+                let mut supports = [0; 4];
+                for (direction, support) in supports.iter_mut().enumerate() {
+                    *support = patterns.propagator[opposite(direction)][pattern].len() as Int;
+                }
+                supports
+            })
+            .collect();
+        let weight_sum: Real = patterns.weights.iter().sum();
+        let weight_log_sum: Real = patterns.weights.iter().map(|w| w * w.ln()).sum();
+
+        Self {
+            patterns,
+            columns,
+            rows,
+            possible: vec![vec![true; patterns.len()]; cell_count],
+            compatible: vec![compatible; cell_count],
+            counts: vec![patterns.len(); cell_count],
+            weight_sums: vec![weight_sum; cell_count],
+            weight_log_sums: vec![weight_log_sum; cell_count],
+            banned: vec![],
+        }
+    }
+
+    fn cell(&self, x: Int, y: Int) -> Index {
+        (y * self.columns + x) as Index
+    }
+
+    fn ban(&mut self, cell: Index, pattern: Index) {
+        if !self.possible[cell][pattern] {
+            return;
+        }
+        let weight = self.patterns.weights[pattern];
+        self.possible[cell][pattern] = false;
+        self.compatible[cell][pattern] = [0; 4];
+        self.counts[cell] -= 1;
+        self.weight_sums[cell] -= weight;
+        self.weight_log_sums[cell] -= weight * weight.ln();
+        self.banned.push((cell, pattern));
+    }
+
+    /// Spreads every pending ban to the neighbours. Returns false on a contradiction.
+    fn propagate(&mut self) -> bool {
+        let patterns = self.patterns;
+        while let Some((cell, pattern)) = self.banned.pop() {
+            let x = cell as Int % self.columns;
+            let y = cell as Int / self.columns;
+            for (direction, (dx, dy)) in OFFSETS.iter().enumerate() {
+                let (nx, ny) = (x + dx, y + dy);
+                if nx < 0 || ny < 0 || nx >= self.columns || ny >= self.rows {
+                    continue;
+                }
+                let neighbour = self.cell(nx, ny);
+                for other in &patterns.propagator[direction][pattern] {
+                    let support = &mut self.compatible[neighbour][*other][direction];
+                    *support -= 1;
+                    if *support == 0 {
+                        self.ban(neighbour, *other);
+                    }
+                }
+            }
+        }
+        self.counts.iter().all(|count| *count > 0)
+    }
+
+    fn entropy(&self, cell: Index) -> Real {
+        let sum = self.weight_sums[cell];
+        sum.ln() - self.weight_log_sums[cell] / sum
+    }
+
+    /// The undecided cell with the lowest entropy, ties broken at random.
+    fn next_cell(&self, rng: &mut RandomNumberGenerator) -> Option<Index> {
+        let mut best: Option<(Index, Real)> = None;
+        for (cell, count) in self.counts.iter().enumerate() {
+            if *count <= 1 {
+                continue;
+            }
+            let entropy = self.entropy(cell) + rng.range(0, 1000) as Real * 1e-6;
+            if best.map_or(true, |(_, lowest)| entropy < lowest) {
+                best = Some((cell, entropy));
+            }
+        }
+        best.map(|(cell, _)| cell)
+    }
+
+    fn observe(&mut self, cell: Index, rng: &mut RandomNumberGenerator) {
+        let mut roll = rng.range(0, 1_000_000) as Real / 1_000_000. * self.weight_sums[cell];
+        let candidates: Vec<Index> = (0..self.patterns.len())
+            .filter(|pattern| self.possible[cell][*pattern])
+            .collect();
+        let mut chosen = *candidates.last().unwrap();
+        for pattern in &candidates {
+            roll -= self.patterns.weights[*pattern];
+            if roll <= 0. {
+                chosen = *pattern;
+                break;
+            }
+        }
+        for pattern in candidates {
+            if pattern != chosen {
+                self.ban(cell, pattern);
+            }
+        }
+    }
+
+    /// Bans patterns that would put floor on the outer edge of the area.
+    fn wall_in_borders(&mut self) {
+        let last = self.patterns.size - 1;
+        for y in 0..self.rows {
+            for x in 0..self.columns {
+                let cell = self.cell(x, y);
+                for pattern in 0..self.patterns.len() {
+                    let open_edge = (0..=last).any(|i| {
+                        let is_floor =
+                            |px, py| self.patterns.tile(pattern, px, py) == TileType::FLOOR;
+                        (x == 0 && is_floor(0, i))
+                            || (x == self.columns - 1 && is_floor(last, i))
+                            || (y == 0 && is_floor(i, 0))
+                            || (y == self.rows - 1 && is_floor(i, last))
+                    });
+                    if open_edge {
+                        self.ban(cell, pattern);
+                    }
+                }
+            }
+        }
+    }
+
+    fn to_area(&self, width: Int, height: Int) -> AreaGrid {
+        let mut area = filled_area(width, height);
+        for y in 0..height {
+            for x in 0..width {
+                let (column, row) = (x.min(self.columns - 1), y.min(self.rows - 1));
+                let cell = self.cell(column, row);
+                if let Some(pattern) = self.possible[cell].iter().position(|p| *p) {
+                    let tile = self.patterns.tile(pattern, x - column, y - row);
+                    area.set_tile(&IVec2::new(x, y), tile);
+                }
+            }
+        }
+        area
+    }
+}
+
+/// Runs one collapse over a `width` by `height` area. Returns `None` when the
+/// constraints contradict each other partway through, in which case the caller
+/// is expected to try again with the advanced rng.
+pub fn collapse(
+    patterns: &Patterns,
+    width: Int,
+    height: Int,
+    border_walls: bool,
+    rng: &mut RandomNumberGenerator,
+) -> Option<AreaGrid> {
+    if patterns.is_empty() || width < patterns.size || height < patterns.size {
+        return None;
+    }
+
+    let mut wave = Wave::new(
+        patterns,
+        width - patterns.size + 1,
+        height - patterns.size + 1,
+    );
+    if border_walls {
+        wave.wall_in_borders();
+    }
+    if !wave.propagate() {
+        return None;
+    }
+
+    while let Some(cell) = wave.next_cell(rng) {
+        wave.observe(cell, rng);
+        if !wave.propagate() {
+            return None;
+        }
+    }
+    Some(wave.to_area(width, height))
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::math::IVec2;
+    use rltk::RandomNumberGenerator;
+
+    use crate::world::{generator::wfc::Sample, TileType};
+
+    use super::{collapse, Patterns};
+
+    fn stripes() -> Sample {
+        Sample::from_ascii(
+            "#.#.#.
+             #.#.#.
+             #.#.#.
+             #.#.#.",
+        )
+    }
+
+    #[test]
+    fn learns_distinct_patterns() {
+        let patterns = Patterns::learn(&[stripes()], 2);
+        assert_eq!(2, patterns.len());
+    }
+
+    #[test]
+    fn output_keeps_sample_adjacency() {
+        let patterns = Patterns::learn(&[stripes()], 2);
+        let mut rng = RandomNumberGenerator::seeded(5);
+        let area = collapse(&patterns, 12, 8, false, &mut rng).expect("Contradiction");
+
+        for y in 0..8 {
+            for x in 0..11 {
+                let here = area.tile_at(&IVec2::new(x, y)).unwrap().which();
+                let right = area.tile_at(&IVec2::new(x + 1, y)).unwrap().which();
+                let below = area
+                    .tile_at(&IVec2::new(x, (y + 1).min(7)))
+                    .unwrap()
+                    .which();
+                assert_ne!(here, right);
+                assert_eq!(here, below);
+            }
+        }
+    }
+
+    #[test]
+    fn borders_stay_walled() {
+        let sample = Sample::from_ascii(
+            "##########
+             ##########
+             ##......##
+             ##.####.##
+             ##.#..#.##
+             ##......##
+             ##########
+             ##########",
+        );
+        let patterns = Patterns::learn(&[sample], 3);
+        let mut rng = RandomNumberGenerator::seeded(9);
+        let area = (0..20)
+            .find_map(|_| collapse(&patterns, 20, 14, true, &mut rng))
+            .expect("Every attempt contradicted");
+
+        for x in 0..20 {
+            for y in [0, 13] {
+                assert_eq!(
+                    TileType::WALL,
+                    area.tile_at(&IVec2::new(x, y)).unwrap().which()
+                );
+            }
+        }
+        for y in 0..14 {
+            for x in [0, 19] {
+                assert_eq!(
+                    TileType::WALL,
+                    area.tile_at(&IVec2::new(x, y)).unwrap().which()
+                );
+            }
+        }
+    }
+}
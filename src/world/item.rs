@@ -0,0 +1,7 @@
+use bevy::prelude::Component;
+
+/// Something lying on the floor, named after the marker that spawned it.
+#[derive(Debug, Clone, Component)]
+pub struct Item {
+    pub kind: String,
+}
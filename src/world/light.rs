@@ -0,0 +1,160 @@
+use bevy::prelude::*;
+use serde::Deserialize;
+
+use crate::{
+    core::types::{Int, Real},
+    util::algorithms::symmetric_shadowcasting,
+};
+
+use super::WorldMap;
+
+/// Light level of a fully lit tile.
+pub const FULL_LIGHT: Real = 1.0;
+/// Light level below which a tile is too dark to make anything out.
+pub const VISIBILITY_THRESHOLD: Real = 0.2;
+
+fn lit() -> bool {
+    true
+}
+
+fn linear() -> Real {
+    1.0
+}
+
+/// Something giving off light, either an entity or a tile through its definition.
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize, Component)]
+pub struct LightSource {
+    pub radius: Int,
+    pub color: (Real, Real, Real),
+    /// Light level on the source's own tile.
+    pub intensity: Real,
+    /// How sharply the light fades towards the edge of its radius. 1 fades evenly.
+    #[serde(default = "linear")]
+    pub falloff: Real,
+    #[serde(default = "lit")]
+    pub lit: bool,
+}
+
+impl LightSource {
+    pub fn color(&self) -> Color {
+        let (r, g, b) = self.color;
+        Color::rgb(r, g, b)
+    }
+
+    /// Light level `distance` tiles away from the source.
+    pub fn level_at(&self, distance: Real) -> Real {
+        let reach = self.radius as Real + 1.0;
+        self.intensity * (1.0 - distance / reach).max(0.0).powf(self.falloff)
+    }
+}
+
+/// Sees in the dark up to `range` tiles away.
+#[derive(Debug, Clone, Copy, Component)]
+pub struct Darkvision {
+    pub range: Int,
+}
+
+/// Light falling on a tile.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Light {
+    pub intensity: Real,
+    pub color: Color,
+}
+
+impl Light {
+    pub fn ambient(level: Real) -> Self {
+        Self {
+            intensity: level,
+            color: Color::WHITE,
+        }
+    }
+
+    /// Adds light of another color, the mix leaning towards whichever is brighter.
+    pub fn add(&mut self, intensity: Real, color: Color) {
+        let total = self.intensity + intensity;
+        if total <= 0.0 {
+            return;
+        }
+        let mix = |own: Real, other: Real| (own * self.intensity + other * intensity) / total;
+        self.color = Color::rgb(
+            mix(self.color.r(), color.r()),
+            mix(self.color.g(), color.g()),
+            mix(self.color.b(), color.b()),
+        );
+        self.intensity = total;
+    }
+
+    pub fn is_bright_enough(&self) -> bool {
+        self.intensity >= VISIBILITY_THRESHOLD
+    }
+}
+
+/// Every tile a source at `origin` lights up, with the level it reaches there.
+/// Opaque tiles stop the light but are lit themselves.
+pub fn cast_light(
+    world_map: &WorldMap,
+    origin: &IVec2,
+    source: &LightSource,
+) -> Vec<(IVec2, Real)> {
+    let reach = source.radius as Real + 0.5;
+    symmetric_shadowcasting(origin, &|delta| delta.as_vec2().length() <= reach, &|pos| {
+        world_map.is_opaque(pos)
+    })
+    .into_iter()
+    .map(|pos| {
+        let distance = (pos - *origin).as_vec2().length();
+        (pos, source.level_at(distance))
+    })
+    .filter(|(_, level)| *level > 0.0)
+    .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::prelude::*;
+
+    use crate::{test::helpers::visibility::from_ascii_layout, world::WorldMap};
+
+    use super::{cast_light, Light, LightSource};
+
+    fn torch() -> LightSource {
+        LightSource {
+            radius: 4,
+            color: (1.0, 0.5, 0.0),
+            intensity: 1.0,
+            falloff: 1.0,
+            lit: true,
+        }
+    }
+
+    #[test]
+    fn light_fades_and_stops_at_walls() {
+        let (origin, area) = from_ascii_layout(
+            "#########
+             #@..#...#
+             #########",
+        );
+        let mut world_map = WorldMap::default();
+        world_map.insert_offset(&IVec2::ZERO, area);
+
+        let lit = cast_light(&world_map, &origin, &torch());
+        let level = |x| {
+            lit.iter()
+                .find(|(pos, _)| *pos == IVec2::new(x, 1))
+                .map(|(_, level)| *level)
+        };
+        assert_eq!(Some(1.0), level(1));
+        assert!(level(2).unwrap() > level(3).unwrap());
+        assert!(level(4).is_some(), "The wall itself is lit");
+        assert_eq!(None, level(5));
+    }
+
+    #[test]
+    fn mixing_leans_towards_brighter_light() {
+        let mut light = Light::ambient(0.2);
+        light.add(0.6, Color::RED);
+        assert!((light.intensity - 0.8).abs() < 1e-6);
+        assert!(light.color.r() > light.color.g());
+        assert!(light.color.g() > 0.0);
+    }
+}
@@ -1,38 +1,34 @@
-use bevy::{math::IVec2, prelude::Component};
+use bevy::{
+    math::IVec2,
+    prelude::{Component, Entity},
+};
 
 use std::collections::HashMap;
 
 use rltk::{Algorithm2D, BaseMap, Point};
 
-use crate::core::types::{Index, Int};
+use crate::core::types::{Index, Int, Real};
 
-use super::Renderable;
+use super::{TileType, FULL_LIGHT};
 
-#[derive(Debug, PartialEq, Copy, Clone)]
-pub enum TileType {
-    Wall,
-    Floor,
+/// An entity standing on a tile, as recorded in the spatial index.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Occupant {
+    pub entity: Entity,
+    pub is_actor: bool,
 }
 
-impl Default for TileType {
-    fn default() -> Self {
-        Self::Wall
-    }
-}
-
-// pub struct Tile {
-//     pub kind: TileType,
-//     pub content: Vec<Entity>,
-// }
-
 #[derive(Debug, Clone, Component)]
 pub struct AreaGrid {
     pub tiles: Vec<TileType>,
-    pub renderables: HashMap<IVec2, Renderable>,
+    /// Entities on each tile, by local position, in the order they arrived.
+    pub occupants: HashMap<IVec2, Vec<Occupant>>,
     pub width: Int,
     pub height: Int,
     pub revealed: Vec<bool>,
     pub visible: Vec<bool>,
+    /// Light level everywhere in the area before any light source adds to it.
+    pub ambient_light: Real,
 }
 
 impl Default for AreaGrid {
@@ -43,7 +39,8 @@ impl Default for AreaGrid {
             height: Default::default(),
             revealed: vec![false; 80 * 50],
             visible: vec![false; 80 * 50],
-            renderables: HashMap::default(),
+            occupants: HashMap::default(),
+            ambient_light: FULL_LIGHT,
         }
     }
 }
@@ -93,9 +90,8 @@ impl<'a> TileHandleMut<'a> {
         self.map.revealed[self.index] = is_revealed;
     }
 
-    pub fn set_renderable(&mut self, renderable: Renderable) {
-        let pos = self.map.index_to_point(self.index);
-        self.map.renderables.insert(pos, renderable);
+    pub fn set_type(&mut self, tile_type: TileType) {
+        self.map.tiles[self.index] = tile_type;
     }
 
     pub fn is_visible(&mut self) -> bool {
@@ -111,12 +107,13 @@ impl AreaGrid {
     pub fn new(dimensions: &IVec2) -> Self {
         let tile_count = (dimensions.x * dimensions.y) as usize;
         Self {
-            tiles: vec![TileType::Wall; tile_count],
+            tiles: vec![TileType::WALL; tile_count],
             width: dimensions.y,
             height: dimensions.x,
             revealed: vec![false; tile_count],
             visible: vec![false; tile_count],
-            renderables: HashMap::default(),
+            occupants: HashMap::default(),
+            ambient_light: FULL_LIGHT,
         }
     }
 
@@ -128,7 +125,8 @@ impl AreaGrid {
             height: dimensions.x,
             revealed: vec![false; tile_count],
             visible: vec![false; tile_count],
-            renderables: HashMap::default(),
+            occupants: HashMap::default(),
+            ambient_light: FULL_LIGHT,
         }
     }
 
@@ -154,25 +152,32 @@ impl AreaGrid {
         }
     }
 
+    pub fn set_tile(&mut self, position: &IVec2, tile_type: TileType) {
+        let idx = self.xy_idx(position.x, position.y);
+        self.tiles[idx] = tile_type;
+    }
+
+    /// Whether the tile can't be walked onto. Out of bounds counts as open.
     pub fn is_blocking(&self, position: &IVec2) -> bool {
         if !self.is_point_in_bounds(position) {
             return false;
         }
-        match self.at(position) {
-            TileType::Wall => true,
-            TileType::Floor => false,
-        }
+        !self.at(position).is_walkable()
     }
 
-    pub fn clear_renderables(&mut self) {
-        self.renderables.drain();
+    /// Whether the tile can't be seen through. Out of bounds counts as clear.
+    pub fn is_opaque(&self, position: &IVec2) -> bool {
+        if !self.is_point_in_bounds(position) {
+            return false;
+        }
+        !self.at(position).is_transparent()
     }
 
-    fn index_to_point(&self, index: Index) -> IVec2 {
+    pub fn index_to_point(&self, index: Index) -> IVec2 {
         IVec2::new(index as Int % self.width, index as Int / self.width)
     }
 
-    fn is_point_in_bounds(&self, point: &IVec2) -> bool {
+    pub fn is_point_in_bounds(&self, point: &IVec2) -> bool {
         point.x >= 0 && point.x < self.width && point.y >= 0 && point.y < self.height
     }
 
@@ -215,7 +220,7 @@ impl Iterator for MapIterator {
 
 impl BaseMap for AreaGrid {
     fn is_opaque(&self, idx: Index) -> bool {
-        self.tiles[idx] == TileType::Wall
+        !self.tiles[idx].is_transparent()
     }
 }
 
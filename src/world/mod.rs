@@ -1,14 +1,32 @@
 pub mod generator;
 
 mod map;
-pub use map::{AreaGrid, TileType};
+pub use map::{AreaGrid, Occupant, TileHandle, TileHandleMut};
+
+mod tile;
+pub use tile::{TileDefinition, TileDefinitions, TileType, BASE_MOVE_COST, TILE_DEFINITIONS};
+
+mod light;
+pub use light::*;
 
 mod viewshed;
-pub use viewshed::Viewshed;
+pub use viewshed::{ViewModifier, Viewshed};
 
 mod renderable;
 pub use renderable::Renderable;
 
+mod dungeon;
+pub use dungeon::*;
+
+mod item;
+pub use item::Item;
+
+mod door;
+pub use door::*;
+
+mod noise;
+pub use noise::{propagate_noise, NoiseEvent};
+
 mod world_map;
 pub use world_map::*;
 
@@ -0,0 +1,66 @@
+use std::collections::HashMap;
+
+use bevy::math::IVec2;
+
+use crate::{core::types::Int, util::algorithms::pathfinding::dijkstra_map};
+
+use super::WorldMap;
+
+/// Extra loudness lost when a sound travels through a wall tile.
+const WALL_ATTENUATION: Int = 4;
+
+#[derive(Debug, Clone, Copy)]
+pub struct NoiseEvent {
+    pub origin: IVec2,
+    pub loudness: Int,
+}
+
+/// Loudness of a noise as heard on every tile it reaches.
+pub fn propagate_noise(world_map: &WorldMap, noise: &NoiseEvent) -> HashMap<IVec2, Int> {
+    let costs = dijkstra_map(&[noise.origin], noise.loudness, &|pos| {
+        world_map.tile_at(pos)?;
+        if world_map.is_blocking(pos) {
+            Some(1 + WALL_ATTENUATION)
+        } else {
+            Some(1)
+        }
+    });
+
+    costs
+        .into_iter()
+        .map(|(pos, cost)| (pos, noise.loudness - cost))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::math::IVec2;
+
+    use crate::{
+        test::helpers::visibility::from_ascii_layout,
+        world::{NoiseEvent, WorldMap},
+    };
+
+    use super::propagate_noise;
+
+    #[test]
+    fn walls_muffle_noise() {
+        let (origin, area) = from_ascii_layout(
+            "#########
+             #@..#...#
+             #########",
+        );
+        let noise = NoiseEvent {
+            origin,
+            loudness: 10,
+        };
+        let mut world_map = WorldMap::default();
+        world_map.insert_offset(&IVec2::ZERO, area);
+        let heard = propagate_noise(&world_map, &noise);
+
+        assert_eq!(Some(&10), heard.get(&origin));
+        assert_eq!(Some(&8), heard.get(&IVec2::new(3, 1)));
+        assert_eq!(Some(&2), heard.get(&IVec2::new(5, 1)));
+        assert!(heard.get(&IVec2::new(7, 1)).is_some());
+    }
+}
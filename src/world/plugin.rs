@@ -1,11 +1,14 @@
 use bevy::prelude::*;
 
-use crate::{AppState, SystemLabels};
+use crate::{actors::ActorSystems, AppState, SystemLabels};
 
 use super::{
-    generator::generate_map_system,
-    systems::{apply_player_viewsheds, update_renderables, update_viewsheds},
-    WorldMap,
+    generator::{builders::GeneratorSettings, generate_map_system},
+    systems::{
+        apply_player_viewsheds, change_level, invalidate_lighting, pick_up_keys,
+        refresh_viewsheds_near_terrain, update_lighting, update_spatial_index, update_viewsheds,
+    },
+    Dungeon, LevelChangeEvent, NoiseEvent, TerrainChangedEvent, WorldMap,
 };
 
 pub struct GameWorldPlugin;
@@ -13,21 +16,41 @@ pub struct GameWorldPlugin;
 impl Plugin for GameWorldPlugin {
     fn build(&self, app: &mut App) {
         app.init_resource::<WorldMap>()
+            .init_resource::<Dungeon>()
+            .init_resource::<GeneratorSettings>()
+            .add_event::<NoiseEvent>()
+            .add_event::<LevelChangeEvent>()
+            .add_event::<TerrainChangedEvent>()
             .add_startup_system(generate_map_system.label(SystemLabels::Generation))
+            .add_system_set(
+                SystemSet::on_update(AppState::Running)
+                    .after(ActorSystems::Action)
+                    .with_system(change_level)
+                    .with_system(refresh_viewsheds_near_terrain)
+                    .with_system(pick_up_keys),
+            )
+            .add_system_set(
+                SystemSet::on_exit(AppState::Running)
+                    .label(MapSystems::Lighting)
+                    .with_system(update_lighting),
+            )
             .add_system_set(
                 SystemSet::on_exit(AppState::Running)
                     .label(MapSystems::Viewshed)
+                    .after(MapSystems::Lighting)
                     .with_system(update_viewsheds),
             )
             .add_system_set(
                 SystemSet::on_exit(AppState::Running)
                     .with_system(apply_player_viewsheds.after(MapSystems::Viewshed)),
             )
-            .add_system_set(SystemSet::on_exit(AppState::Running).with_system(update_renderables));
+            .add_system_to_stage(CoreStage::PostUpdate, update_spatial_index)
+            .add_system_to_stage(CoreStage::PostUpdate, invalidate_lighting);
     }
 }
 
 #[derive(Debug, Hash, PartialEq, Eq, Clone, SystemLabel)]
 enum MapSystems {
+    Lighting,
     Viewshed,
 }
@@ -0,0 +1,92 @@
+use bevy::prelude::*;
+
+use crate::{
+    core::types::GridPos,
+    world::{Item, Keyring, TerrainChangedEvent, Viewshed, WorldMap, KEY_ITEM},
+};
+
+/// Marks the viewsheds of every actor that could see a changed tile for recomputing.
+pub fn refresh_viewsheds_near_terrain(
+    mut terrain_events: EventReader<TerrainChangedEvent>,
+    mut viewers: Query<(&GridPos, &mut Viewshed)>,
+) {
+    for event in terrain_events.iter() {
+        for (pos, mut viewshed) in viewers.iter_mut() {
+            if viewshed.may_see(&pos.0, &event.position) {
+                viewshed.dirty = true;
+            }
+        }
+    }
+}
+
+/// Actors carrying a keyring pocket any key they stand on.
+pub fn pick_up_keys(
+    mut commands: Commands,
+    world_map: Res<WorldMap>,
+    mut holders: Query<(&GridPos, &mut Keyring)>,
+    items: Query<&Item>,
+) {
+    for (pos, mut keyring) in holders.iter_mut() {
+        for occupant in world_map.occupants_at(&pos.0) {
+            if let Ok(item) = items.get(occupant.entity) {
+                if item.kind == KEY_ITEM {
+                    keyring.keys += 1;
+                    commands.entity(occupant.entity).despawn();
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::prelude::*;
+
+    use crate::{
+        core::types::GridPos,
+        util::algorithms::field_of_view::FOV,
+        world::{TerrainChangedEvent, Viewshed},
+    };
+
+    use super::refresh_viewsheds_near_terrain;
+
+    #[test]
+    fn only_nearby_viewers_refresh() {
+        let mut world = World::new();
+        let mut events = Events::<TerrainChangedEvent>::default();
+        events.send(TerrainChangedEvent {
+            position: IVec2::new(5, 0),
+        });
+        world.insert_resource(events);
+
+        let near = world
+            .spawn()
+            .insert(GridPos(IVec2::ZERO))
+            .insert(Viewshed {
+                fov: FOV::Omnidirectional(8),
+                ..Default::default()
+            })
+            .id();
+        let watching = world
+            .spawn()
+            .insert(GridPos(IVec2::new(40, 0)))
+            .insert(Viewshed {
+                visible_tiles: vec![IVec2::new(5, 0)].into_iter().collect(),
+                fov: FOV::Omnidirectional(1),
+                ..Default::default()
+            })
+            .id();
+        let far = world
+            .spawn()
+            .insert(GridPos(IVec2::new(40, 0)))
+            .insert(Viewshed::default())
+            .id();
+
+        let mut stage = SystemStage::single(refresh_viewsheds_near_terrain.system());
+        stage.run(&mut world);
+
+        assert!(world.get::<Viewshed>(near).unwrap().dirty);
+        assert!(world.get::<Viewshed>(watching).unwrap().dirty);
+        assert!(!world.get::<Viewshed>(far).unwrap().dirty);
+    }
+}
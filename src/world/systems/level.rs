@@ -0,0 +1,225 @@
+use std::collections::HashMap;
+
+use bevy::prelude::*;
+
+use crate::{
+    actors::{
+        compute_next_position,
+        effects::{Effect, Hazard, HazardDefinitions},
+        Actor, Player, Projectile, Statuses, Weapon,
+    },
+    ai::{Awareness, Monster, MonsterDefinitions, Squad, SquadMember},
+    core::types::{Direction, GridPos},
+    world::{
+        generator::{builders::GeneratorSettings, generate_level, spawn_monster},
+        Dungeon, HazardSnapshot, Item, ItemSnapshot, LevelChangeEvent, MonsterSnapshot, Renderable,
+        StoredLevel, TileType, Viewshed, WorldMap,
+    },
+};
+
+/// Moves the player to another depth. The level being left is stored with its
+/// monsters, items and hazards, and the one being entered is restored or generated. Anything
+/// still in flight is lost along the way.
+#[allow(clippy::too_many_arguments)]
+pub fn change_level(
+    mut commands: Commands,
+    mut level_events: EventReader<LevelChangeEvent>,
+    mut world_map: ResMut<WorldMap>,
+    mut dungeon: ResMut<Dungeon>,
+    settings: Res<GeneratorSettings>,
+    monster_definitions: Res<MonsterDefinitions>,
+    hazard_definitions: Res<HazardDefinitions>,
+    mut players: Query<(&Children, &Actor, &mut Viewshed), With<Player>>,
+    monsters: Query<(
+        Entity,
+        &Monster,
+        &Actor,
+        &GridPos,
+        &Statuses,
+        Option<&Awareness>,
+        Option<&SquadMember>,
+    )>,
+    items: Query<(Entity, &Item, &GridPos, &Renderable)>,
+    squads: Query<Entity, With<Squad>>,
+    hazards: Query<(Entity, &Hazard, &Effect, &GridPos)>,
+    effects: Query<Entity, (With<Effect>, Without<Hazard>)>,
+    projectiles: Query<Entity, With<Projectile>>,
+    weapons: Query<Entity, With<Weapon>>,
+) {
+    // Monsters may take the stairs in the same frame, but only the player changes level.
+    let event = match level_events
+        .iter()
+        .filter(|event| players.get(event.entity).is_ok())
+        .last()
+    {
+        Some(event) => *event,
+        None => return,
+    };
+    let (children, player, mut viewshed) = match players.get_mut(event.entity) {
+        Ok(player) => player,
+        Err(_) => return,
+    };
+    let depth = dungeon.depth + event.delta;
+    if depth < 0 {
+        return;
+    }
+    let stairs = if event.delta > 0 {
+        TileType::UP_STAIRS
+    } else {
+        TileType::DOWN_STAIRS
+    };
+    // Checked before the current level is torn down, so that the player stays put
+    // when there is nowhere to arrive.
+    let revisit_arrival = match dungeon.level(depth) {
+        Some(level) => match level.world_map.find_tile(stairs) {
+            Some(arrival) => Some(arrival),
+            None => {
+                warn!("No stairs to arrive on at depth {}", depth);
+                return;
+            }
+        },
+        None => None,
+    };
+
+    let mut left_map = std::mem::take(&mut *world_map);
+    left_map.clear_occupants();
+    let leaving = StoredLevel {
+        world_map: left_map,
+        monsters: monsters
+            .iter()
+            .map(
+                |(_, monster, actor, pos, statuses, awareness, member)| MonsterSnapshot {
+                    kind: monster.kind.clone(),
+                    position: pos.0,
+                    actor: actor.clone(),
+                    statuses: statuses.clone(),
+                    awareness: awareness.copied().unwrap_or_default(),
+                    squad: member.map(|member| member.squad),
+                },
+            )
+            .collect(),
+        items: items
+            .iter()
+            .map(|(_, item, pos, renderable)| ItemSnapshot {
+                item: item.clone(),
+                position: pos.0,
+                renderable: *renderable,
+            })
+            .collect(),
+        hazards: hazards
+            .iter()
+            .map(|(_, hazard, effect, pos)| HazardSnapshot {
+                kind: hazard.kind.clone(),
+                position: pos.0,
+                time_left: effect.time_left,
+                reach: hazard.reach,
+            })
+            .collect(),
+    };
+    let left_behind = monsters
+        .iter()
+        .map(|(entity, ..)| entity)
+        .chain(items.iter().map(|(entity, ..)| entity))
+        .chain(squads.iter())
+        .chain(hazards.iter().map(|(entity, ..)| entity))
+        .chain(effects.iter())
+        .chain(projectiles.iter());
+    for entity in left_behind {
+        commands.entity(entity).despawn_recursive();
+    }
+    let previous_depth = dungeon.depth;
+    dungeon.store(previous_depth, leaving);
+    dungeon.depth = depth;
+
+    let arrival = match (revisit_arrival, dungeon.take(depth)) {
+        (Some(arrival), Some(level)) => {
+            restore_level(
+                &mut commands,
+                level,
+                &mut world_map,
+                &monster_definitions,
+                &hazard_definitions,
+            );
+            arrival
+        }
+        _ => generate_level(
+            &mut commands,
+            &mut world_map,
+            &settings,
+            &monster_definitions,
+            &hazard_definitions,
+            depth,
+            dungeon.level_seed(depth),
+        ),
+    };
+
+    commands.entity(event.entity).insert(GridPos(arrival));
+    let weapon_pos = compute_next_position(Direction::Forward, player.facing, &arrival);
+    for weapon in children.iter().filter(|child| weapons.get(**child).is_ok()) {
+        commands.entity(*weapon).insert(GridPos(weapon_pos));
+    }
+    viewshed.dirty = true;
+    info!("Entered depth {}", depth);
+}
+
+fn restore_level(
+    commands: &mut Commands,
+    level: StoredLevel,
+    world_map: &mut WorldMap,
+    monster_definitions: &MonsterDefinitions,
+    hazard_definitions: &HazardDefinitions,
+) {
+    *world_map = level.world_map;
+    // The light was cast for whoever stood on the level when it was left.
+    world_map.invalidate_lights();
+
+    let mut squads: HashMap<Entity, Entity> = HashMap::new();
+    for snapshot in level.monsters {
+        let definition = match monster_definitions.get(&snapshot.kind) {
+            Some(definition) => definition,
+            None => continue,
+        };
+        let monster = spawn_monster(
+            commands,
+            definition,
+            snapshot.position,
+            snapshot.actor.facing,
+        );
+        let mut viewshed = Viewshed::with_fov(definition.fov.clone());
+        for status in snapshot.statuses.active.iter() {
+            if let Some(modifier) = status.kind.view_modifier() {
+                viewshed.add_modifier(modifier);
+            }
+        }
+        commands
+            .entity(monster)
+            .insert(snapshot.actor)
+            .insert(snapshot.statuses)
+            .insert(snapshot.awareness)
+            .insert(viewshed);
+        if let Some(old_squad) = snapshot.squad {
+            let squad = *squads
+                .entry(old_squad)
+                .or_insert_with(|| commands.spawn().insert(Squad::default()).id());
+            commands.entity(monster).insert(SquadMember::of(squad));
+        }
+    }
+
+    for snapshot in level.items {
+        commands
+            .spawn()
+            .insert(snapshot.item)
+            .insert(GridPos(snapshot.position))
+            .insert(snapshot.renderable);
+    }
+
+    for snapshot in level.hazards {
+        let definition = match hazard_definitions.get(&snapshot.kind) {
+            Some(definition) => definition,
+            None => continue,
+        };
+        let mut hazard = definition.drifted_bundle(snapshot.position, snapshot.reach);
+        hazard.effect.time_left = snapshot.time_left;
+        commands.spawn_bundle(hazard);
+    }
+}
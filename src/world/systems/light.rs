@@ -0,0 +1,172 @@
+use std::collections::HashMap;
+
+use bevy::prelude::*;
+
+use crate::{
+    core::types::GridPos,
+    world::{cast_light, Light, LightSource, TerrainChangedEvent, Viewshed, WorldMap},
+};
+
+/// Invalidates the light on terrain changes and when light sources go away. These
+/// only show up for a frame, while lighting waits for the game to pause.
+pub fn invalidate_lighting(
+    mut world_map: ResMut<WorldMap>,
+    mut terrain_events: EventReader<TerrainChangedEvent>,
+    removed: RemovedComponents<LightSource>,
+) {
+    if terrain_events.iter().count() > 0 || removed.iter().next().is_some() {
+        world_map.invalidate_lights();
+    }
+}
+
+/// Recasts every lit source, from entities and tiles alike, once the light is out of
+/// date or a source changed or moved. Viewsheds are marked for recomputing when the
+/// light changed on a tile they could see.
+pub fn update_lighting(
+    mut world_map: ResMut<WorldMap>,
+    sources: Query<(&GridPos, &LightSource)>,
+    changed_sources: Query<(), Changed<LightSource>>,
+    moved_sources: Query<(), (With<LightSource>, Changed<GridPos>)>,
+    mut viewsheds: Query<(&GridPos, &mut Viewshed)>,
+) {
+    if world_map.lights_cast() && changed_sources.is_empty() && moved_sources.is_empty() {
+        return;
+    }
+
+    let emitters: Vec<(IVec2, LightSource)> = sources
+        .iter()
+        .map(|(pos, source)| (pos.0, *source))
+        .chain(world_map.tile_light_sources())
+        .filter(|(_, source)| source.lit)
+        .collect();
+
+    let mut lights: HashMap<IVec2, Light> = HashMap::new();
+    for (origin, source) in &emitters {
+        for (pos, level) in cast_light(&world_map, origin, source) {
+            lights
+                .entry(pos)
+                .or_insert_with(|| Light::ambient(0.0))
+                .add(level, source.color());
+        }
+    }
+
+    let changed = world_map.set_lights(lights);
+    if changed.is_empty() {
+        return;
+    }
+    for (pos, mut viewshed) in viewsheds.iter_mut() {
+        if changed
+            .iter()
+            .any(|light_pos| viewshed.may_see(&pos.0, light_pos))
+        {
+            viewshed.dirty = true;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::prelude::*;
+
+    use crate::{
+        core::types::GridPos,
+        test::helpers::{visibility::from_ascii_layout, world::world_with_area},
+        util::algorithms::field_of_view::FOV,
+        world::{LightSource, TerrainChangedEvent, TileType, Viewshed, WorldMap},
+    };
+
+    use super::{invalidate_lighting, update_lighting};
+
+    #[test]
+    fn only_viewers_in_reach_of_new_light_refresh() {
+        let (_, area) = from_ascii_layout(
+            "##############################
+             #@...........................#
+             ##############################",
+        );
+        let mut world_map = WorldMap::default();
+        world_map.insert_offset(&IVec2::ZERO, area);
+        let mut world = World::new();
+        world.insert_resource(world_map);
+
+        world
+            .spawn()
+            .insert(GridPos(IVec2::new(2, 1)))
+            .insert(LightSource {
+                radius: 2,
+                color: (1.0, 1.0, 1.0),
+                intensity: 1.0,
+                falloff: 1.0,
+                lit: true,
+            });
+        let near = world
+            .spawn()
+            .insert(GridPos(IVec2::new(6, 1)))
+            .insert(Viewshed::with_fov(FOV::Omnidirectional(4)))
+            .id();
+        let far = world
+            .spawn()
+            .insert(GridPos(IVec2::new(27, 1)))
+            .insert(Viewshed::with_fov(FOV::Omnidirectional(4)))
+            .id();
+        for viewer in [near, far] {
+            world.get_mut::<Viewshed>(viewer).unwrap().dirty = false;
+        }
+
+        SystemStage::single(update_lighting.system()).run(&mut world);
+
+        assert!(world.get::<Viewshed>(near).unwrap().dirty);
+        assert!(!world.get::<Viewshed>(far).unwrap().dirty);
+    }
+
+    #[test]
+    fn light_is_only_recast_after_changes() {
+        let (_, mut area) = from_ascii_layout(
+            "########
+             #@.....#
+             ########",
+        );
+        area.ambient_light = 0.0;
+        let mut world = world_with_area(area);
+        world.insert_resource(Events::<TerrainChangedEvent>::default());
+        world
+            .spawn()
+            .insert(GridPos(IVec2::new(1, 1)))
+            .insert(LightSource {
+                radius: 5,
+                color: (1.0, 1.0, 1.0),
+                intensity: 1.0,
+                falloff: 1.0,
+                lit: true,
+            });
+        let wall = IVec2::new(2, 1);
+        let behind_wall = IVec2::new(4, 1);
+        let light_behind_wall = |world: &World| {
+            let world_map = world.get_resource::<WorldMap>().unwrap();
+            world_map.light_at(&behind_wall).intensity
+        };
+
+        let mut invalidate = SystemStage::single(invalidate_lighting.system());
+        let mut update = SystemStage::single(update_lighting.system());
+        update.run(&mut world);
+        let lit = light_behind_wall(&world);
+        assert!(lit > 0.0);
+
+        world
+            .get_resource_mut::<WorldMap>()
+            .unwrap()
+            .tile_at_mut(&wall)
+            .unwrap()
+            .set_type(TileType::WALL);
+        update.run(&mut world);
+        assert_eq!(lit, light_behind_wall(&world));
+
+        world
+            .get_resource_mut::<Events<TerrainChangedEvent>>()
+            .unwrap()
+            .send(TerrainChangedEvent { position: wall });
+        invalidate.run(&mut world);
+        update.run(&mut world);
+        assert_eq!(0.0, light_behind_wall(&world));
+    }
+}
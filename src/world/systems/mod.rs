@@ -1,6 +1,14 @@
+mod door;
+pub use door::*;
 
-mod renderable;
-pub use renderable::*;
+mod level;
+pub use level::*;
+
+mod light;
+pub use light::*;
+
+mod spatial_index;
+pub use spatial_index::*;
 
 mod viewshed;
 pub use viewshed::*;
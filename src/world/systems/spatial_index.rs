@@ -0,0 +1,69 @@
+use bevy::prelude::*;
+
+use crate::{
+    actors::Actor,
+    core::types::GridPos,
+    world::{Occupant, WorldMap},
+};
+
+/// Keeps the spatial index in step with every `GridPos` that was added, changed or removed.
+pub fn update_spatial_index(
+    mut world_map: ResMut<WorldMap>,
+    moved: Query<(Entity, &GridPos, Option<&Actor>), Changed<GridPos>>,
+    removed: RemovedComponents<GridPos>,
+) {
+    for entity in removed.iter() {
+        world_map.remove_occupant(entity);
+    }
+    for (entity, pos, actor) in moved.iter() {
+        let occupant = Occupant {
+            entity,
+            is_actor: actor.is_some(),
+        };
+        world_map.place_occupant(occupant, &pos.0);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::prelude::*;
+
+    use crate::{
+        actors::ActorBundle,
+        core::types::GridPos,
+        world::{generator::builders::filled_area, WorldMap},
+    };
+
+    use super::update_spatial_index;
+
+    #[test]
+    fn index_tracks_spawns_moves_and_despawns() {
+        let mut world = World::new();
+        let mut world_map = WorldMap::default();
+        world_map.insert_offset(&IVec2::ZERO, filled_area(10, 10));
+        world.insert_resource(world_map);
+
+        let actor = world
+            .spawn()
+            .insert_bundle(ActorBundle {
+                position: GridPos(IVec2::new(2, 2)),
+                ..Default::default()
+            })
+            .id();
+        let item = world.spawn().insert(GridPos(IVec2::new(4, 4))).id();
+
+        let mut stage = SystemStage::single(update_spatial_index.system());
+        stage.run(&mut world);
+        let world_map = world.get_resource::<WorldMap>().unwrap();
+        assert_eq!(Some(actor), world_map.blocking_entity_at(&IVec2::new(2, 2)));
+        assert_eq!(item, world_map.occupants_at(&IVec2::new(4, 4))[0].entity);
+
+        world.get_mut::<GridPos>(actor).unwrap().0 = IVec2::new(3, 2);
+        world.despawn(item);
+        stage.run(&mut world);
+        let world_map = world.get_resource::<WorldMap>().unwrap();
+        assert_eq!(None, world_map.blocking_entity_at(&IVec2::new(2, 2)));
+        assert_eq!(Some(actor), world_map.blocking_entity_at(&IVec2::new(3, 2)));
+        assert!(world_map.occupants_at(&IVec2::new(4, 4)).is_empty());
+    }
+}
@@ -2,25 +2,56 @@ use bevy::prelude::*;
 
 use crate::{
     actors::{Actor, Player},
-    core::types::GridPos,
-    util::algorithms::{field_of_view::FOV, symmetric_shadowcasting},
-    world::{Viewshed, WorldMap},
+    core::types::{GridPos, Int},
+    util::{
+        algorithms::{geometry::chessboard_distance, shadowcast},
+        helpers::GridSet,
+    },
+    world::{Darkvision, Viewshed, WorldMap},
 };
 
+/// Rectangle a viewshed can cover: `reach` tiles around `origin`, within the map.
+fn view_bounds(world_map: &WorldMap, origin: &IVec2, reach: Int) -> Option<(IVec2, IVec2)> {
+    let (map_min, map_max) = world_map.bounds()?;
+    let reach_min = IVec2::new(
+        origin.x.saturating_sub(reach),
+        origin.y.saturating_sub(reach),
+    );
+    let reach_max = IVec2::new(
+        origin.x.saturating_add(reach),
+        origin.y.saturating_add(reach),
+    );
+    Some((map_min.max(reach_min), map_max.min(reach_max)))
+}
+
+/// Recomputes dirty viewsheds. Tiles in the field of view are only seen when lit
+/// well enough, or close enough for darkvision. Actors always know their own tile.
 pub fn update_viewsheds(
-    world_map: ResMut<WorldMap>,
-    mut query: Query<(&mut Viewshed, &GridPos, &Actor)>,
+    world_map: Res<WorldMap>,
+    mut query: Query<(&mut Viewshed, &GridPos, &Actor, Option<&Darkvision>)>,
 ) {
-    for (mut viewshed, pos, actor) in query.iter_mut() {
+    for (mut viewshed, pos, actor, darkvision) in query.iter_mut() {
         if viewshed.dirty {
             viewshed.dirty = false;
 
-            let area = &world_map.get_area_from_pos(&pos.0).unwrap().1;
-            let fov = FOV::Quadratic(15, 0.35, -1.5);
-            viewshed.visible_tiles =
-                symmetric_shadowcasting(&pos.0, &|pos| fov.sees(pos, actor.facing), &|pos| {
-                    area.is_blocking(pos)
-                });
+            let fov = viewshed.effective_fov();
+            let dark_range = darkvision.map_or(-1, |darkvision| darkvision.range);
+            let mut visible_tiles = match view_bounds(&world_map, &pos.0, fov.range()) {
+                Some((min, max)) => GridSet::new(min, max),
+                None => GridSet::default(),
+            };
+            shadowcast(
+                &pos.0,
+                &mut |seen| {
+                    let is_seen = *seen == pos.0
+                        || chessboard_distance(&pos.0, seen) <= dark_range
+                        || world_map.light_at(seen).is_bright_enough();
+                    is_seen && visible_tiles.insert(*seen)
+                },
+                &|pos| fov.sees(pos, actor.facing),
+                &|pos| world_map.is_opaque(pos),
+            );
+            viewshed.visible_tiles = visible_tiles;
         }
     }
 }
@@ -29,15 +60,110 @@ pub fn apply_player_viewsheds(
     mut world_map: ResMut<WorldMap>,
     query: Query<(&GridPos, &Viewshed), With<Player>>,
 ) {
-    let (pos, viewshed) = query.single();
-    let area = &mut world_map.get_area_from_pos_mut(&pos.0).unwrap().1;
+    let (_, viewshed) = query.single();
 
-    for t in area.visible.iter_mut() {
-        *t = false
-    }
+    world_map.clear_visible();
     for visible_position in &viewshed.visible_tiles {
-        let mut tile = area.tile_at_mut(visible_position).unwrap();
-        tile.set_visible(true);
-        tile.set_revealed(true);
+        if let Some(mut tile) = world_map.tile_at_mut(&visible_position) {
+            tile.set_visible(true);
+            tile.set_revealed(true);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::prelude::*;
+
+    use crate::{
+        actors::ActorBundle,
+        core::types::{GridPos, Real},
+        test::helpers::{visibility::from_ascii_layout, world::world_with_area},
+        util::algorithms::field_of_view::FOV,
+        world::{Darkvision, ViewModifier, Viewshed, FULL_LIGHT},
+    };
+
+    use super::update_viewsheds;
+
+    fn corridor(ambient_light: Real) -> (World, IVec2) {
+        let (origin, mut area) = from_ascii_layout(
+            "#######
+             #.....#
+             #.....#
+             #..@..#",
+        );
+        area.ambient_light = ambient_light;
+        (world_with_area(area), origin)
+    }
+
+    fn spawn_viewer(world: &mut World, origin: IVec2) -> Entity {
+        world
+            .spawn()
+            .insert_bundle(ActorBundle {
+                position: GridPos(origin),
+                viewshed: Viewshed::with_fov(FOV::Omnidirectional(8)),
+                ..Default::default()
+            })
+            .id()
+    }
+
+    #[test]
+    fn darkness_hides_everything_but_the_own_tile() {
+        let (mut world, origin) = corridor(0.0);
+        let viewer = spawn_viewer(&mut world, origin);
+
+        SystemStage::single(update_viewsheds.system()).run(&mut world);
+
+        let viewshed = world.get::<Viewshed>(viewer).unwrap();
+        assert_eq!(
+            vec![origin],
+            viewshed.visible_tiles.iter().collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn darkvision_sees_nearby_tiles() {
+        let (mut world, origin) = corridor(0.0);
+        let viewer = spawn_viewer(&mut world, origin);
+        world.entity_mut(viewer).insert(Darkvision { range: 1 });
+
+        SystemStage::single(update_viewsheds.system()).run(&mut world);
+
+        let viewshed = world.get::<Viewshed>(viewer).unwrap();
+        assert!(viewshed
+            .visible_tiles
+            .contains(&(origin + IVec2::new(0, -1))));
+        assert!(!viewshed
+            .visible_tiles
+            .contains(&(origin + IVec2::new(0, -2))));
+    }
+
+    #[test]
+    fn blinded_viewers_see_only_their_own_tile() {
+        let (mut world, origin) = corridor(FULL_LIGHT);
+        let viewer = spawn_viewer(&mut world, origin);
+
+        SystemStage::single(update_viewsheds.system()).run(&mut world);
+        assert!(
+            world
+                .get::<Viewshed>(viewer)
+                .unwrap()
+                .visible_tiles
+                .iter()
+                .count()
+                > 1
+        );
+
+        world
+            .get_mut::<Viewshed>(viewer)
+            .unwrap()
+            .add_modifier(ViewModifier::Blinded);
+        SystemStage::single(update_viewsheds.system()).run(&mut world);
+
+        let viewshed = world.get::<Viewshed>(viewer).unwrap();
+        assert_eq!(
+            vec![origin],
+            viewshed.visible_tiles.iter().collect::<Vec<_>>()
+        );
     }
 }
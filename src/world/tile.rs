@@ -0,0 +1,169 @@
+use bevy::prelude::Color;
+use once_cell::sync::Lazy;
+use serde::Deserialize;
+
+use crate::{
+    core::types::{Int, Real},
+    util::helpers::deserialize,
+};
+
+use super::LightSource;
+
+/// Movement cost of an ordinary tile, in percent of a normal step.
+pub const BASE_MOVE_COST: Int = 100;
+
+fn base_move_cost() -> Int {
+    BASE_MOVE_COST
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct TileDefinition {
+    pub name: String,
+    pub walkable: bool,
+    pub transparent: bool,
+    /// Time a step onto this tile takes, in percent of a normal step.
+    #[serde(default = "base_move_cost")]
+    pub cost: Int,
+    pub glyph: char,
+    pub fg: (Real, Real, Real),
+    pub bg: (Real, Real, Real),
+    #[serde(default)]
+    pub flammable: bool,
+    #[serde(default)]
+    pub destructible: bool,
+    #[serde(default)]
+    pub light: Option<LightSource>,
+    pub description: String,
+}
+
+impl TileDefinition {
+    pub fn fg(&self) -> Color {
+        let (r, g, b) = self.fg;
+        Color::rgb(r, g, b)
+    }
+
+    pub fn bg(&self) -> Color {
+        let (r, g, b) = self.bg;
+        Color::rgb(r, g, b)
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct TileDefinitions {
+    pub tiles: Vec<TileDefinition>,
+}
+
+impl Default for TileDefinitions {
+    fn default() -> Self {
+        let definitions: Self = deserialize("assets/data/tiles.ron");
+        for (tile_type, name) in TileType::BUILT_IN {
+            assert_eq!(
+                Some(name),
+                definitions
+                    .tiles
+                    .get(tile_type.0 as usize)
+                    .map(|t| t.name.as_str()),
+                "tiles.ron must start with the built-in tiles, in order"
+            );
+        }
+        definitions
+    }
+}
+
+/// Loaded once and shared, since tile properties are needed wherever a map is read.
+pub static TILE_DEFINITIONS: Lazy<TileDefinitions> = Lazy::new(TileDefinitions::default);
+
+/// Index into the tile definitions. The first few are built in because the
+/// generators and stairs rely on them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TileType(pub u16);
+
+impl TileType {
+    pub const WALL: TileType = TileType(0);
+    pub const FLOOR: TileType = TileType(1);
+    pub const UP_STAIRS: TileType = TileType(2);
+    pub const DOWN_STAIRS: TileType = TileType(3);
+    pub const CLOSED_DOOR: TileType = TileType(4);
+    pub const OPEN_DOOR: TileType = TileType(5);
+    pub const LOCKED_DOOR: TileType = TileType(6);
+
+    const BUILT_IN: [(TileType, &'static str); 7] = [
+        (Self::WALL, "wall"),
+        (Self::FLOOR, "floor"),
+        (Self::UP_STAIRS, "up stairs"),
+        (Self::DOWN_STAIRS, "down stairs"),
+        (Self::CLOSED_DOOR, "closed door"),
+        (Self::OPEN_DOOR, "open door"),
+        (Self::LOCKED_DOOR, "locked door"),
+    ];
+
+    pub fn named(name: &str) -> Option<TileType> {
+        TILE_DEFINITIONS
+            .tiles
+            .iter()
+            .position(|definition| definition.name == name)
+            .map(|index| TileType(index as u16))
+    }
+
+    pub fn definition(&self) -> &'static TileDefinition {
+        &TILE_DEFINITIONS.tiles[self.0 as usize]
+    }
+
+    pub fn is_walkable(&self) -> bool {
+        self.definition().walkable
+    }
+
+    pub fn is_transparent(&self) -> bool {
+        self.definition().transparent
+    }
+
+    pub fn cost(&self) -> Int {
+        self.definition().cost
+    }
+
+    pub fn is_flammable(&self) -> bool {
+        self.definition().flammable
+    }
+
+    /// Doors in any state. Even shut, they still connect the rooms either side.
+    pub fn is_door(&self) -> bool {
+        matches!(
+            *self,
+            Self::CLOSED_DOOR | Self::OPEN_DOOR | Self::LOCKED_DOOR
+        )
+    }
+}
+
+impl Default for TileType {
+    fn default() -> Self {
+        Self::WALL
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{TileType, BASE_MOVE_COST};
+
+    #[test]
+    fn built_in_tiles() {
+        assert!(!TileType::WALL.is_walkable());
+        assert!(!TileType::WALL.is_transparent());
+        assert!(TileType::FLOOR.is_walkable());
+        assert_eq!(BASE_MOVE_COST, TileType::FLOOR.cost());
+        assert_eq!(Some(TileType::DOWN_STAIRS), TileType::named("down stairs"));
+    }
+
+    #[test]
+    fn see_through_but_blocking() {
+        let window = TileType::named("window").unwrap();
+        assert!(window.is_transparent());
+        assert!(!window.is_walkable());
+
+        let bush = TileType::named("bush").unwrap();
+        assert!(bush.is_walkable());
+        assert!(!bush.is_transparent());
+
+        let water = TileType::named("deep water").unwrap();
+        assert!(water.cost() > BASE_MOVE_COST);
+    }
+}
@@ -1,20 +1,123 @@
 use bevy::{math::IVec2, prelude::Component};
+use serde::Deserialize;
 
-use crate::core::types::Int;
+use crate::{
+    core::types::{Int, Real},
+    util::{
+        algorithms::{field_of_view::FOV, geometry::chessboard_distance},
+        helpers::GridSet,
+    },
+};
+
+/// Half-angle, in radians, left to an actor with tunnel vision.
+pub const TUNNEL_VISION_ANGLE: Real = 0.3;
+
+/// Temporary change to how an actor sees, layered over its own field of view.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+pub enum ViewModifier {
+    /// Sees nothing but its own tile.
+    Blinded,
+    /// Watches every direction, as far as it can normally see.
+    Alert,
+    /// Sees only a narrow cone straight ahead.
+    TunnelVision,
+}
+
+impl ViewModifier {
+    fn apply(&self, fov: FOV) -> FOV {
+        match self {
+            ViewModifier::Blinded => FOV::Omnidirectional(0),
+            ViewModifier::Alert => match fov {
+                FOV::Infinite => FOV::Infinite,
+                fov => FOV::Omnidirectional(fov.range()),
+            },
+            ViewModifier::TunnelVision => FOV::Cone(fov.range(), TUNNEL_VISION_ANGLE),
+        }
+    }
+}
 
 #[derive(Debug, Default, Component)]
 pub struct Viewshed {
-    pub visible_tiles: Vec<IVec2>,
-    pub range: Int,
+    pub visible_tiles: GridSet,
+    /// The actor's own field of view, before any modifiers.
+    pub fov: FOV,
+    /// Tiles added to the reach of the actor's field of view, or taken away when negative.
+    pub range_bonus: Int,
+    pub modifiers: Vec<ViewModifier>,
     pub dirty: bool,
 }
 
 impl Viewshed {
-    pub fn with_range(range: Int) -> Self {
+    pub fn with_fov(fov: FOV) -> Self {
         Self {
-            range,
+            fov,
             dirty: true,
             ..Default::default()
         }
     }
+
+    /// Field of view after the range bonus and modifiers. Blindness overrides everything else.
+    pub fn effective_fov(&self) -> FOV {
+        let fov = self.fov.extended(self.range_bonus);
+        if self.modifiers.contains(&ViewModifier::Blinded) {
+            return ViewModifier::Blinded.apply(fov);
+        }
+        self.modifiers
+            .iter()
+            .fold(fov, |fov, modifier| modifier.apply(fov))
+    }
+
+    /// Whether a change at `pos` could affect what the viewer at `origin` sees,
+    /// either because the tile is in view or because it lies within reach.
+    pub fn may_see(&self, origin: &IVec2, pos: &IVec2) -> bool {
+        self.visible_tiles.contains(pos)
+            || chessboard_distance(origin, pos) <= self.effective_fov().range()
+    }
+
+    pub fn add_modifier(&mut self, modifier: ViewModifier) {
+        self.modifiers.push(modifier);
+        self.dirty = true;
+    }
+
+    /// Removes one instance of the modifier. Returns whether there was one.
+    pub fn remove_modifier(&mut self, modifier: ViewModifier) -> bool {
+        match self.modifiers.iter().position(|own| *own == modifier) {
+            Some(index) => {
+                self.modifiers.remove(index);
+                self.dirty = true;
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::util::algorithms::field_of_view::FOV;
+
+    use super::{ViewModifier, Viewshed, TUNNEL_VISION_ANGLE};
+
+    #[test]
+    fn modifiers_reshape_the_view() {
+        let mut viewshed = Viewshed::with_fov(FOV::Cone(8, 1.0));
+        viewshed.dirty = false;
+
+        viewshed.add_modifier(ViewModifier::Alert);
+        assert!(viewshed.dirty);
+        assert_eq!(FOV::Omnidirectional(8), viewshed.effective_fov());
+
+        viewshed.add_modifier(ViewModifier::TunnelVision);
+        assert_eq!(FOV::Cone(8, TUNNEL_VISION_ANGLE), viewshed.effective_fov());
+
+        viewshed.add_modifier(ViewModifier::Blinded);
+        assert_eq!(FOV::Omnidirectional(0), viewshed.effective_fov());
+
+        assert!(viewshed.remove_modifier(ViewModifier::Blinded));
+        assert!(!viewshed.remove_modifier(ViewModifier::Blinded));
+        assert_eq!(FOV::Cone(8, 1.0), viewshed.fov);
+
+        viewshed.range_bonus = 2;
+        assert_eq!(FOV::Cone(10, TUNNEL_VISION_ANGLE), viewshed.effective_fov());
+    }
 }
@@ -1,14 +1,34 @@
-use bevy::math::IVec2;
+use std::collections::HashMap;
+
 pub use bevy::prelude::Component;
+use bevy::{math::IVec2, prelude::Entity};
 
-use super::AreaGrid;
+use super::{
+    AreaGrid, Light, LightSource, Occupant, TileHandle, TileHandleMut, TileType, FULL_LIGHT,
+};
 
 #[derive(Debug)]
 pub struct OffsetArea(pub IVec2, pub AreaGrid);
 
+impl OffsetArea {
+    pub fn to_local(&self, pos: &IVec2) -> IVec2 {
+        *pos - self.0
+    }
+
+    pub fn contains(&self, pos: &IVec2) -> bool {
+        self.1.is_point_in_bounds(&self.to_local(pos))
+    }
+}
+
 #[derive(Debug, Default, Component)]
 pub struct WorldMap {
     areas: Vec<OffsetArea>,
+    /// Where each indexed entity was last recorded, so it can be found again when it moves.
+    occupant_positions: HashMap<Entity, IVec2>,
+    /// Light from every source, on the tiles it reaches. Ambient light comes on top.
+    lights: HashMap<IVec2, Light>,
+    /// Whether `lights` was cast since the terrain last changed.
+    lights_cast: bool,
 }
 
 impl WorldMap {
@@ -29,13 +49,234 @@ impl WorldMap {
             area.tile_at(&(*pos - *offset)).is_some()
         })
     }
+
+    pub fn areas(&self) -> impl Iterator<Item = &OffsetArea> {
+        self.areas.iter()
+    }
+
+    pub fn areas_mut(&mut self) -> impl Iterator<Item = &mut OffsetArea> {
+        self.areas.iter_mut()
+    }
+
+    /// The tile at a world position, in whichever area covers it.
+    pub fn tile_at(&self, pos: &IVec2) -> Option<TileHandle> {
+        let offset_area = self.get_area_from_pos(pos)?;
+        offset_area.1.tile_at(&offset_area.to_local(pos))
+    }
+
+    pub fn tile_at_mut(&mut self, pos: &IVec2) -> Option<TileHandleMut> {
+        let offset_area = self.get_area_from_pos_mut(pos)?;
+        let local = offset_area.to_local(pos);
+        offset_area.1.tile_at_mut(&local)
+    }
+
+    /// Whether a world position can't be walked onto. Positions outside every area
+    /// count as blocking, so nothing leaves the known world.
+    pub fn is_blocking(&self, pos: &IVec2) -> bool {
+        match self.get_area_from_pos(pos) {
+            Some(offset_area) => offset_area.1.is_blocking(&offset_area.to_local(pos)),
+            None => true,
+        }
+    }
+
+    /// Whether a world position can't be seen through. Like `is_blocking`, positions
+    /// outside every area count as opaque.
+    pub fn is_opaque(&self, pos: &IVec2) -> bool {
+        match self.get_area_from_pos(pos) {
+            Some(offset_area) => offset_area.1.is_opaque(&offset_area.to_local(pos)),
+            None => true,
+        }
+    }
+
+    /// Whether a world position holds a door that is shut, locked or not.
+    pub fn is_closed_door(&self, pos: &IVec2) -> bool {
+        self.tile_at(pos).map_or(false, |tile| {
+            let tile = tile.which();
+            tile.is_door() && !tile.is_walkable()
+        })
+    }
+
+    /// Smallest and largest world positions covered by any area.
+    pub fn bounds(&self) -> Option<(IVec2, IVec2)> {
+        self.areas
+            .iter()
+            .map(|OffsetArea(offset, area)| {
+                (
+                    *offset,
+                    *offset + IVec2::new(area.width - 1, area.height - 1),
+                )
+            })
+            .reduce(|(min_a, max_a), (min_b, max_b)| (min_a.min(min_b), max_a.max(max_b)))
+    }
+
+    /// Records `occupant` as standing at `pos`, taking it off wherever it stood before.
+    /// Positions outside every area leave the entity unindexed.
+    pub fn place_occupant(&mut self, occupant: Occupant, pos: &IVec2) {
+        self.remove_occupant(occupant.entity);
+        let offset_area = match self.get_area_from_pos_mut(pos) {
+            Some(offset_area) => offset_area,
+            None => return,
+        };
+        let local = offset_area.to_local(pos);
+        offset_area
+            .1
+            .occupants
+            .entry(local)
+            .or_default()
+            .push(occupant);
+        self.occupant_positions.insert(occupant.entity, *pos);
+    }
+
+    /// Moves an indexed entity to `pos`, keeping what is known about it.
+    pub fn move_occupant(&mut self, entity: Entity, pos: &IVec2) {
+        if let Some(occupant) = self.remove_occupant(entity) {
+            self.place_occupant(occupant, pos);
+        }
+    }
+
+    pub fn remove_occupant(&mut self, entity: Entity) -> Option<Occupant> {
+        let pos = self.occupant_positions.remove(&entity)?;
+        let offset_area = self.get_area_from_pos_mut(&pos)?;
+        let local = offset_area.to_local(&pos);
+        let occupants = offset_area.1.occupants.get_mut(&local)?;
+        let index = occupants
+            .iter()
+            .position(|occupant| occupant.entity == entity)?;
+        let occupant = occupants.remove(index);
+        if occupants.is_empty() {
+            offset_area.1.occupants.remove(&local);
+        }
+        Some(occupant)
+    }
+
+    /// Forgets every indexed entity, as when the level is put away and its entities despawned.
+    pub fn clear_occupants(&mut self) {
+        self.occupant_positions.clear();
+        for OffsetArea(_, area) in &mut self.areas {
+            area.occupants.clear();
+        }
+    }
+
+    pub fn occupants_at(&self, pos: &IVec2) -> &[Occupant] {
+        self.get_area_from_pos(pos)
+            .and_then(|offset_area| offset_area.1.occupants.get(&offset_area.to_local(pos)))
+            .map(|occupants| occupants.as_slice())
+            .unwrap_or(&[])
+    }
+
+    pub fn actors_at<'a>(&'a self, pos: &IVec2) -> impl Iterator<Item = Entity> + 'a {
+        self.occupants_at(pos)
+            .iter()
+            .filter(|occupant| occupant.is_actor)
+            .map(|occupant| occupant.entity)
+    }
+
+    /// The entity keeping others off a tile. Only actors take up a whole tile.
+    pub fn blocking_entity_at(&self, pos: &IVec2) -> Option<Entity> {
+        self.actors_at(pos).next()
+    }
+
+    /// Every occupant on the given positions, with the position it stands on.
+    pub fn occupants_in(&self, positions: &[IVec2]) -> Vec<(IVec2, Occupant)> {
+        positions
+            .iter()
+            .flat_map(|pos| {
+                self.occupants_at(pos)
+                    .iter()
+                    .map(move |occupant| (*pos, *occupant))
+            })
+            .collect()
+    }
+
+    /// Every occupant inside the rectangle from `min` to `max`, both included.
+    pub fn occupants_within(&self, min: &IVec2, max: &IVec2) -> Vec<(IVec2, Occupant)> {
+        let positions: Vec<IVec2> = (min.y..=max.y)
+            .flat_map(|y| (min.x..=max.x).map(move |x| IVec2::new(x, y)))
+            .collect();
+        self.occupants_in(&positions)
+    }
+
+    /// Ambient light of the area plus whatever sources shine on the tile.
+    /// Positions outside every area are dark.
+    pub fn light_at(&self, pos: &IVec2) -> Light {
+        let ambient = self
+            .get_area_from_pos(pos)
+            .map_or(0.0, |offset_area| offset_area.1.ambient_light);
+        let mut light = Light::ambient(ambient);
+        if let Some(source_light) = self.lights.get(pos) {
+            light.add(source_light.intensity, source_light.color);
+        }
+        light.intensity = light.intensity.min(FULL_LIGHT);
+        light
+    }
+
+    /// Replaces the light from sources. Returns the tiles whose light changed.
+    pub fn set_lights(&mut self, lights: HashMap<IVec2, Light>) -> Vec<IVec2> {
+        let changed = self
+            .lights
+            .iter()
+            .filter(|(pos, light)| lights.get(pos) != Some(light))
+            .map(|(pos, _)| *pos)
+            .chain(
+                lights
+                    .keys()
+                    .filter(|pos| !self.lights.contains_key(pos))
+                    .copied(),
+            )
+            .collect();
+        self.lights = lights;
+        self.lights_cast = true;
+        changed
+    }
+
+    pub fn lights_cast(&self) -> bool {
+        self.lights_cast
+    }
+
+    /// Asks for the light to be cast again, for instance after terrain changed.
+    pub fn invalidate_lights(&mut self) {
+        self.lights_cast = false;
+    }
+
+    /// Tiles that give off light through their definition, with their world position.
+    pub fn tile_light_sources(&self) -> Vec<(IVec2, LightSource)> {
+        self.areas
+            .iter()
+            .flat_map(|OffsetArea(offset, area)| {
+                area.tiles
+                    .iter()
+                    .enumerate()
+                    .filter_map(move |(index, tile)| {
+                        tile.definition()
+                            .light
+                            .map(|light| (*offset + area.index_to_point(index), light))
+                    })
+            })
+            .collect()
+    }
+
+    /// World position of the first tile of the given type, searching areas in insertion order.
+    pub fn find_tile(&self, tile_type: TileType) -> Option<IVec2> {
+        self.areas.iter().find_map(|OffsetArea(offset, area)| {
+            area.tiles
+                .iter()
+                .position(|tile| *tile == tile_type)
+                .map(|index| *offset + area.index_to_point(index))
+        })
+    }
+
+    pub fn clear_visible(&mut self) {
+        for OffsetArea(_, area) in &mut self.areas {
+            area.visible.iter_mut().for_each(|visible| *visible = false);
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use bevy::math::IVec2;
+    use bevy::{math::IVec2, prelude::Entity};
 
-    use crate::world::AreaGrid;
+    use crate::world::{generator::builders::filled_area, AreaGrid, Occupant, TileType};
 
     use super::WorldMap;
 
@@ -61,4 +302,59 @@ mod tests {
         let area = world_map.get_area_from_pos(&IVec2::ZERO);
         assert!(area.is_none());
     }
+
+    #[test]
+    fn tiles_across_areas() {
+        let mut east = filled_area(10, 5);
+        east.set_tile(&IVec2::new(0, 2), TileType::FLOOR);
+
+        let mut world_map = WorldMap::default();
+        world_map.insert_offset(&IVec2::ZERO, filled_area(10, 5));
+        world_map.insert_offset(&IVec2::new(10, 0), east);
+
+        assert!(world_map.is_blocking(&IVec2::new(9, 2)));
+        assert!(!world_map.is_blocking(&IVec2::new(10, 2)));
+        assert!(world_map.is_blocking(&IVec2::new(20, 2)));
+        assert!(world_map.tile_at(&IVec2::new(-1, 0)).is_none());
+        assert_eq!(Some((IVec2::ZERO, IVec2::new(19, 4))), world_map.bounds());
+    }
+
+    #[test]
+    fn occupants_follow_their_entity() {
+        let mut world_map = WorldMap::default();
+        world_map.insert_offset(&IVec2::ZERO, filled_area(10, 5));
+        world_map.insert_offset(&IVec2::new(10, 0), filled_area(10, 5));
+
+        let actor = Occupant {
+            entity: Entity::from_raw(1),
+            is_actor: true,
+        };
+        let item = Occupant {
+            entity: Entity::from_raw(2),
+            is_actor: false,
+        };
+        world_map.place_occupant(actor, &IVec2::new(9, 2));
+        world_map.place_occupant(item, &IVec2::new(12, 2));
+        assert_eq!(
+            Some(actor.entity),
+            world_map.blocking_entity_at(&IVec2::new(9, 2))
+        );
+        assert_eq!(None, world_map.blocking_entity_at(&IVec2::new(12, 2)));
+
+        world_map.move_occupant(actor.entity, &IVec2::new(12, 2));
+        assert!(world_map.occupants_at(&IVec2::new(9, 2)).is_empty());
+        assert_eq!(&[item, actor], world_map.occupants_at(&IVec2::new(12, 2)));
+        assert_eq!(
+            2,
+            world_map
+                .occupants_within(&IVec2::new(11, 0), &IVec2::new(13, 4))
+                .len()
+        );
+
+        world_map.remove_occupant(item.entity);
+        assert_eq!(
+            vec![(IVec2::new(12, 2), actor)],
+            world_map.occupants_in(&[IVec2::new(9, 2), IVec2::new(12, 2)])
+        );
+    }
 }